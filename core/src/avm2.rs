@@ -69,6 +69,8 @@ pub mod script;
 pub mod specification;
 mod string;
 mod stubs;
+#[cfg(test)]
+pub(crate) mod test_utils;
 mod traits;
 mod value;
 pub mod vector;
@@ -76,7 +78,7 @@ mod verify;
 mod vtable;
 
 pub use crate::avm2::activation::Activation;
-pub use crate::avm2::array::ArrayStorage;
+pub use crate::avm2::array::{array_to_f32_buf, ArrayStorage};
 pub use crate::avm2::call_stack::{CallNode, CallStack};
 pub use crate::avm2::class::Class;
 #[allow(unused)] // For debug_ui
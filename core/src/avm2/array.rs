@@ -1,6 +1,8 @@
 //! Array support types
 
+use crate::avm2::error::{make_error_2004, Error2004Type};
 use crate::avm2::value::Value;
+use crate::avm2::{Activation, Error};
 use gc_arena::Collect;
 use std::collections::BTreeMap;
 
@@ -529,6 +531,30 @@ impl<'gc> ArrayStorage<'gc> {
     }
 }
 
+/// Converts exactly `N` values (such as the elements of an array) into floats, for native
+/// code that takes matrices or other fixed-size groups of numbers.
+///
+/// Numbers are read directly, anything else is coerced, which may call into ActionScript.
+/// Throws an `ArgumentError` if there aren't exactly `N` values.
+pub fn array_to_f32_buf<'gc, const N: usize>(
+    activation: &mut Activation<'_, 'gc>,
+    values: &[Value<'gc>],
+) -> Result<[f32; N], Error<'gc>> {
+    if values.len() != N {
+        return Err(make_error_2004(activation, Error2004Type::ArgumentError));
+    }
+
+    let mut buf = [0.0; N];
+    for (float, value) in buf.iter_mut().zip(values) {
+        *float = match value {
+            Value::Number(n) => *n as f32,
+            Value::Integer(i) => *i as f32,
+            _ => value.coerce_to_number(activation)? as f32,
+        };
+    }
+    Ok(buf)
+}
+
 impl<'gc, V> FromIterator<V> for ArrayStorage<'gc>
 where
     V: Into<Value<'gc>>,
@@ -97,6 +97,8 @@ pub struct SystemClasses<'gc> {
     pub object_vector: ClassObject<'gc>,
     pub soundtransform: ClassObject<'gc>,
     pub soundchannel: ClassObject<'gc>,
+    pub camera: ClassObject<'gc>,
+    pub microphone: ClassObject<'gc>,
     pub bitmap: ClassObject<'gc>,
     pub bitmapdata: ClassObject<'gc>,
     pub date: ClassObject<'gc>,
@@ -159,6 +161,7 @@ pub struct SystemClasses<'gc> {
     pub netstatusevent: ClassObject<'gc>,
     pub shaderfilter: ClassObject<'gc>,
     pub statusevent: ClassObject<'gc>,
+    pub activityevent: ClassObject<'gc>,
     pub asyncerrorevent: ClassObject<'gc>,
     pub contextmenuevent: ClassObject<'gc>,
     pub filereference: ClassObject<'gc>,
@@ -260,6 +263,8 @@ impl<'gc> SystemClasses<'gc> {
             object_vector: object,
             soundtransform: object,
             soundchannel: object,
+            camera: object,
+            microphone: object,
             bitmap: object,
             bitmapdata: object,
             date: object,
@@ -322,6 +327,7 @@ impl<'gc> SystemClasses<'gc> {
             netstatusevent: object,
             shaderfilter: object,
             statusevent: object,
+            activityevent: object,
             asyncerrorevent: object,
             contextmenuevent: object,
             filereference: object,
@@ -684,6 +690,7 @@ pub fn init_native_system_classes(activation: &mut Activation<'_, '_>) {
             ("flash.events", "UncaughtErrorEvents", uncaughterrorevents),
             ("flash.events", "NetStatusEvent", netstatusevent),
             ("flash.events", "StatusEvent", statusevent),
+            ("flash.events", "ActivityEvent", activityevent),
             ("flash.events", "AsyncErrorEvent", asyncerrorevent),
             ("flash.events", "ContextMenuEvent", contextmenuevent),
             ("flash.events", "FocusEvent", focusevent),
@@ -694,7 +701,9 @@ pub fn init_native_system_classes(activation: &mut Activation<'_, '_>) {
             ("flash.geom", "Rectangle", rectangle),
             ("flash.geom", "Transform", transform),
             ("flash.geom", "ColorTransform", colortransform),
+            ("flash.media", "Camera", camera),
             ("flash.media", "ID3Info", id3info),
+            ("flash.media", "Microphone", microphone),
             ("flash.media", "SoundChannel", soundchannel),
             ("flash.media", "SoundTransform", soundtransform),
             ("flash.media", "Video", video),
@@ -21,6 +21,9 @@ package flash.display {
         public function set value(value:Array):void {
             // FIXME - perform validation
             this._value = value.concat();
+            this.clampValue();
         }
+
+        private native function clampValue():void;
     }
 }
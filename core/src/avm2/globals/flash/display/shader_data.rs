@@ -3,7 +3,7 @@ use crate::avm2::object::TObject;
 use crate::avm2::parameters::ParametersExt;
 use crate::avm2::value::Value;
 use crate::avm2::Error;
-use crate::pixel_bender::PixelBenderTypeExt;
+use crate::pixel_bender::{parameter_values_as_avm2_object, PixelBenderTypeExt};
 use crate::string::AvmString;
 
 use ruffle_macros::istr;
@@ -40,6 +40,8 @@ pub fn init<'gc>(
         activation,
     )?;
 
+    let default_values = parameter_values_as_avm2_object(activation, &shader.params)?;
+
     let mut normal_index = 0;
     let mut texture_index = 0;
 
@@ -65,7 +67,14 @@ pub fn init<'gc>(
         };
 
         let name = AvmString::new_utf8(activation.gc(), name);
-        let param_obj = make_shader_parameter(activation, param, index)?;
+        // Only look at own properties, so that parameters named e.g. `toString`
+        // don't pick up a value from `Object.prototype`.
+        let default_value = if default_values.has_own_property_string(name, activation)? {
+            default_values.get_string_property_local(name, activation)?
+        } else {
+            Value::Undefined
+        };
+        let param_obj = make_shader_parameter(activation, param, index, default_value)?;
         this.set_string_property_local(name, param_obj, activation)?;
     }
 
@@ -61,10 +61,10 @@ pub fn get_shader_args<'gc>(
 
                     if name == OUT_COORD_NAME {
                         // Pass in a dummy value - this will be ignored in favor of the actual pixel coordinate
-                        return Some(PixelBenderShaderArgument::ValueInput {
+                        return Some(Ok(PixelBenderShaderArgument::ValueInput {
                             index: index as u8,
                             value: PixelBenderType::TFloat2(f32::NAN, f32::NAN),
-                        });
+                        }));
                     }
                     let shader_param = shader_data
                         .get_string_property_local(
@@ -89,13 +89,16 @@ pub fn get_shader_args<'gc>(
 
                     let value = shader_param.get_slot(shader_parameter_slots::_VALUE);
 
-                    let pb_val = PixelBenderType::from_avm2_value(activation, value, param_type)
-                        .expect("Failed to convert AVM2 value to PixelBenderType");
+                    let pb_val =
+                        match PixelBenderType::from_avm2_value(activation, value, param_type) {
+                            Ok(pb_val) => pb_val,
+                            Err(e) => return Some(Err(e)),
+                        };
 
-                    Some(PixelBenderShaderArgument::ValueInput {
+                    Some(Ok(PixelBenderShaderArgument::ValueInput {
                         index: index as u8,
                         value: pb_val,
-                    })
+                    }))
                 }
                 PixelBenderParam::Texture {
                     index,
@@ -169,16 +172,16 @@ pub fn get_shader_args<'gc>(
                         None
                     };
 
-                    Some(PixelBenderShaderArgument::ImageInput {
+                    Some(Ok(PixelBenderShaderArgument::ImageInput {
                         index: *index,
                         channels: *channels,
                         name: name.clone(),
                         texture,
-                    })
+                    }))
                 }
             }
         })
-        .collect();
+        .collect::<Result<_, _>>()?;
     Ok((shader_handle.clone(), args))
 }
 
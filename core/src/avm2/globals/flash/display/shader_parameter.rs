@@ -4,16 +4,17 @@ use crate::avm2::globals::slots::flash_display_shader_parameter as parameter_slo
 use crate::avm2::object::TObject;
 use crate::avm2::value::Value;
 use crate::avm2::Error;
-use crate::pixel_bender::PixelBenderTypeExt;
+use crate::pixel_bender::{type_opcode_from_avm2_name, PixelBenderTypeExt};
 use crate::string::AvmString;
 
 use ruffle_macros::istr;
-use ruffle_render::pixel_bender::PixelBenderParam;
+use ruffle_render::pixel_bender::{PixelBenderParam, PixelBenderType, PixelBenderTypeOpcode};
 
 pub fn make_shader_parameter<'gc>(
     activation: &mut Activation<'_, 'gc>,
     param: &PixelBenderParam,
     index: usize,
+    default_value: Value<'gc>,
 ) -> Result<Value<'gc>, Error<'gc>> {
     match param {
         PixelBenderParam::Normal {
@@ -34,14 +35,13 @@ pub fn make_shader_parameter<'gc>(
 
             param_object.set_slot(parameter_slots::_INDEX, index.into(), activation)?;
             param_object.set_slot(parameter_slots::_TYPE, type_name.into(), activation)?;
+            if !matches!(default_value, Value::Undefined) {
+                param_object.set_slot(parameter_slots::_VALUE, default_value, activation)?;
+            }
             for meta in metadata {
                 let name = AvmString::new_utf8(activation.gc(), &meta.key);
                 let value = meta.value.clone().as_avm2_value(activation, false)?;
                 param_value.set_public_property(name, value, activation)?;
-
-                if &*name == b"defaultValue" {
-                    param_object.set_slot(parameter_slots::_VALUE, value, activation)?;
-                }
             }
             param_object.set_string_property_local(
                 istr!("name"),
@@ -70,3 +70,62 @@ pub fn make_shader_parameter<'gc>(
         }
     }
 }
+
+/// Implements `ShaderParameter.clampValue`, which clamps a newly assigned value to the
+/// `minValue` and `maxValue` declared by the kernel.
+pub fn clamp_value<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let min = this.get_public_property(istr!("minValue"), activation)?;
+    let max = this.get_public_property(istr!("maxValue"), activation)?;
+    if matches!(min, Value::Undefined) || matches!(max, Value::Undefined) {
+        return Ok(Value::Undefined);
+    }
+
+    let this = this.as_object().unwrap();
+    let type_name = this
+        .get_slot(parameter_slots::_TYPE)
+        .coerce_to_string(activation)?;
+    let Some(kind) = type_opcode_from_avm2_name(&type_name.to_utf8_lossy()) else {
+        return Ok(Value::Undefined);
+    };
+    if kind == PixelBenderTypeOpcode::TString {
+        return Ok(Value::Undefined);
+    }
+
+    let min = PixelBenderType::from_avm2_value(activation, min, &kind)?;
+    let max = PixelBenderType::from_avm2_value(activation, max, &kind)?;
+
+    // Values with the wrong number of components are left alone,
+    // they'll fail when the shader is run.
+    let value = this.get_slot(parameter_slots::_VALUE);
+    let Some(components) = value.as_object().and_then(|value| {
+        value.as_array_storage().map(|array| {
+            array
+                .iter()
+                .map(|component| component.unwrap_or(Value::Undefined))
+                .collect::<Vec<_>>()
+        })
+    }) else {
+        return Ok(Value::Undefined);
+    };
+    if components.len() != min.len() {
+        return Ok(Value::Undefined);
+    }
+
+    // So are values with components that aren't numbers, instead of clamping the
+    // NaN they'd be converted to.
+    for component in components {
+        if component.coerce_to_number(activation)?.is_nan() {
+            return Ok(Value::Undefined);
+        }
+    }
+
+    let value = PixelBenderType::from_avm2_value(activation, value, &kind)?.clamp(&min, &max);
+    let value = value.as_avm2_value(activation, false)?;
+    this.set_slot(parameter_slots::_VALUE, value, activation)?;
+
+    Ok(Value::Undefined)
+}
@@ -1,5 +1,8 @@
 //! `flash.media` namespace
 
+pub mod camera;
+pub mod devices;
+pub mod microphone;
 pub mod sound;
 pub mod sound_channel;
 pub mod sound_mixer;
@@ -4,8 +4,32 @@ package flash.media {
     import __ruffle__.stub_method;
 
     import flash.events.EventDispatcher;
+    import flash.events.SampleDataEvent;
 
     public final class Microphone extends EventDispatcher {
+        // Microphones that were already handed out, by index, as every call to
+        // `getMicrophone` returns the same object for a device.
+        private static var _microphones:Array = [];
+
+        [Ruffle(NativeAccessible)]
+        private var _index:int = -1;
+
+        [Ruffle(NativeAccessible)]
+        private var _name:String = "";
+
+        // The backend's id of the device, or -1 for a microphone without a device.
+        [Ruffle(NativeAccessible)]
+        private var _deviceId:int = -1;
+
+        private var _gain:Number = 50;
+
+        [Ruffle(NativeAccessible)]
+        private var _rate:int = 8;
+
+        // -1 until the microphone starts capturing.
+        [Ruffle(NativeAccessible)]
+        private var _activityLevel:Number = -1;
+
         [API("672")]
         public static function getEnhancedMicrophone(index:int = -1):Microphone {
             stub_method("flash.media.Microphone", "getEnhancedMicrophone");
@@ -13,14 +37,38 @@ package flash.media {
         }
 
         public static function getMicrophone(index:int = -1):Microphone {
-            stub_method("flash.media.Microphone", "getMicrophone");
-            return new Microphone();
+            // -1 is the default microphone.
+            if (index < 0) {
+                index = 0;
+            }
+            if (!_microphones[index]) {
+                _microphones[index] = createMicrophone(index);
+            }
+            return _microphones[index];
         }
 
+        // Creates a microphone for the device at `index` in `names`, or returns null if
+        // there's no such device.
+        private static native function createMicrophone(index:int):Microphone;
+
         public function setLoopBack(isLooped:Boolean = true):void {
-            stub_method("flash.media.Microphone", "setLoopBack");
+            if (isLooped) {
+                // The captured audio isn't played back, but `activityLevel` is measured.
+                stub_method("flash.media.Microphone", "setLoopBack");
+                this.startCapture();
+            }
+        }
+
+        override public function addEventListener(type:String, listener:Function, useCapture:Boolean = false, priority:int = 0, useWeakReference:Boolean = false):void {
+            super.addEventListener(type, listener, useCapture, priority, useWeakReference);
+            if (type == SampleDataEvent.SAMPLE_DATA) {
+                this.startCapture();
+            }
         }
 
+        // Starts capturing audio from the device, unless it already is.
+        private native function startCapture():void;
+
         public function setSilenceLevel(silenceLevel:Number, timeout:int = -1):void {
             stub_method("flash.media.Microphone", "setSilenceLevel");
         }
@@ -30,8 +78,7 @@ package flash.media {
         }
 
         public function get activityLevel():Number {
-            stub_getter("flash.media.Microphone", "activityLevel");
-            return 0.0;
+            return this._activityLevel;
         }
 
         public function get codec():String {
@@ -82,38 +129,29 @@ package flash.media {
         }
 
         public function get gain():Number {
-            stub_getter("flash.media.Microphone", "gain");
-            return 0.0;
+            return this._gain;
         }
 
         public function set gain(gain:Number) {
-            stub_setter("flash.media.Microphone", "gain");
+            if (isNaN(gain)) {
+                return;
+            }
+            this._gain = Math.max(0, Math.min(100, gain));
         }
 
         public function get index():int {
-            stub_getter("flash.media.Microphone", "index");
-            return 0;
+            return this._index;
         }
 
-        public static function get isSupported():Boolean {
-            stub_getter("flash.media.Microphone", "isSupported");
-            return false;
-        }
+        public static native function get isSupported():Boolean;
 
-        public function get muted():Boolean {
-            stub_getter("flash.media.Microphone", "muted");
-            return true;
-        }
+        public native function get muted():Boolean;
 
         public function get name():String {
-            stub_getter("flash.media.Microphone", "name");
-            return "";
+            return this._name;
         }
 
-        public static function get names():Array {
-            stub_getter("flash.media.Microphone", "names");
-            return [];
-        }
+        public static native function get names():Array;
 
         public function get noiseSuppressionLevel():int {
             stub_getter("flash.media.Microphone", "noiseSuppressionLevel");
@@ -125,13 +163,11 @@ package flash.media {
         }
 
         public function get rate():int {
-            stub_getter("flash.media.Microphone", "rate");
-            return 0;
+            return this._rate;
         }
 
-        public function set rate(level:int) {
-            stub_setter("flash.media.Microphone", "rate");
-        }
+        // Uses the supported rate closest to `rate`.
+        public native function set rate(rate:int):void;
 
         public function get silenceLevel():Number {
             stub_getter("flash.media.Microphone", "silenceLevel");
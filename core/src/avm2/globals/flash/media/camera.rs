@@ -0,0 +1,568 @@
+//! `flash.media.Camera` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::globals::flash::media::devices::{device_names, select_device};
+use crate::avm2::object::{ArrayObject, CameraObject, ScriptObject, TObject};
+use crate::avm2::parameters::ParametersExt;
+use crate::avm2::value::Value;
+use crate::avm2::{ArrayStorage, Error};
+use crate::backend::camera::{CameraDevice, CameraMode, CameraPermission};
+use crate::bitmap::operations;
+use crate::camera::{select_mode, Cameras};
+use crate::string::AvmString;
+use ruffle_macros::istr;
+
+pub use crate::avm2::object::camera_allocator;
+
+/// Implements `Camera`'s constructor
+pub fn init<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    let this = this.as_camera().unwrap();
+
+    // Unlike with `getCamera`, every constructed camera is a new object with its own handle,
+    // even if there already is a camera for the device. Its device is closed once the
+    // movie drops the camera and it's collected.
+    let devices = Cameras::list_devices(activation.context);
+    if let Some(device) = devices.first() {
+        bind_device(activation, this, 0, device);
+    }
+    Ok(Value::Undefined)
+}
+
+/// Implements `Camera.getCamera`
+pub fn get_camera<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let devices = Cameras::list_devices(activation.context);
+    let name = args.try_get_string(activation, 0)?;
+    let Some((index, device)) = select_device(&devices, name, |device| &device.name) else {
+        return Ok(Value::Null);
+    };
+
+    if let Some(camera) = activation.context.cameras.get(activation.gc(), device.id) {
+        return Ok(camera.into());
+    }
+
+    // The constructor is skipped, as it would bind the camera to the first device.
+    // Skipping the constructor of `EventDispatcher` is fine, as all it does is set
+    // `target` to null, which it already is.
+    let class = activation.avm2().classes().camera;
+    let camera = camera_allocator(class, activation)?
+        .as_camera()
+        .expect("Camera allocator should return a Camera");
+    bind_device(activation, camera, index, device);
+
+    Ok(camera.into())
+}
+
+/// Binds a new camera to a device, opening it if the movie may capture from it.
+fn bind_device<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    camera: CameraObject<'gc>,
+    index: usize,
+    device: &CameraDevice,
+) {
+    // A camera that the user denied access to still exists, it just never captures anything.
+    // The same goes for a device that fails to open, except that the movie is told about it.
+    let granted = activation.context.camera_permission == CameraPermission::Granted
+        && Cameras::may_access(activation.context, device);
+    let mut failed = false;
+    let handle = if granted {
+        match activation.context.camera.open(device.id) {
+            Ok(handle) => Some(handle),
+            Err(e) => {
+                tracing::warn!("Failed to open camera {:?}: {e}", device.name);
+                failed = true;
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    camera.set_handle(handle);
+    camera.set_device_id(device.id);
+    camera.set_index(index as u32);
+    camera.set_name(device.name.clone());
+    camera.set_muted(!granted);
+    if let Some(handle) = handle {
+        camera.set_format(activation.context.camera.format(handle).unwrap_or_default());
+    }
+
+    // A failed camera isn't tracked, so that the next `getCamera` tries to open it again.
+    if failed {
+        activation.context.cameras.report_failure(camera);
+    } else {
+        activation.context.cameras.insert(device.id, camera);
+    }
+}
+
+/// Implements `Camera.drawToBitmapData`
+pub fn draw_to_bitmap_data<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    let this = this.as_camera().unwrap();
+
+    let destination = args.get_object(activation, 0, "destination")?;
+    let Some(destination) = destination.as_bitmap_data() else {
+        return Ok(Value::Undefined);
+    };
+    destination.check_valid(activation)?;
+
+    // Nothing is drawn until the camera delivered its first frame.
+    if let Some((width, height, rgba)) = Cameras::grab_frame(activation.context, this) {
+        operations::set_pixels_from_rgba(
+            activation.gc(),
+            activation.context.renderer,
+            destination,
+            width,
+            height,
+            &rgba,
+        );
+    }
+    Ok(Value::Undefined)
+}
+
+/// Implements `Camera.freeze`
+pub fn freeze<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    let this = this.as_camera().unwrap();
+
+    set_frozen(activation, this, true);
+    Ok(Value::Undefined)
+}
+
+/// Implements `Camera.resume`
+pub fn resume<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    let this = this.as_camera().unwrap();
+
+    set_frozen(activation, this, false);
+    Ok(Value::Undefined)
+}
+
+/// Freezes or resumes a camera, reporting it as deactivated while it's frozen.
+fn set_frozen<'gc>(activation: &mut Activation<'_, 'gc>, camera: CameraObject<'gc>, frozen: bool) {
+    if camera.frozen() == frozen {
+        return;
+    }
+    camera.set_frozen(frozen);
+    if let Some(handle) = camera.handle() {
+        activation.context.camera.set_frozen(handle, frozen);
+    }
+
+    Cameras::send_activity(activation.context, camera, !frozen);
+}
+
+/// Implements `Camera.setMotionLevel`
+pub fn set_motion_level<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    let this = this.as_camera().unwrap();
+
+    this.set_motion_level(args.get_i32(activation, 0)?.clamp(0, 100));
+    this.set_motion_timeout(args.get_i32(activation, 1)?.max(0));
+    Ok(Value::Undefined)
+}
+
+/// Implements `Camera.setQuality`
+pub fn set_quality<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    let this = this.as_camera().unwrap();
+
+    // Nothing is encoded yet, the values are only kept for movies that read them back.
+    this.set_bandwidth(args.get_i32(activation, 0)?.max(0));
+    this.set_quality(args.get_i32(activation, 1)?.clamp(0, 100));
+    Ok(Value::Undefined)
+}
+
+/// Implements `Camera.setKeyFrameInterval`
+pub fn set_key_frame_interval<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    let this = this.as_camera().unwrap();
+
+    this.set_key_frame_interval(args.get_i32(activation, 0)?.clamp(1, 1000));
+    Ok(Value::Undefined)
+}
+
+/// Implements `Camera.setCursor`
+pub fn set_cursor<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    let this = this.as_camera().unwrap();
+
+    this.set_cursor(args.get_bool(0));
+    Ok(Value::Undefined)
+}
+
+/// Implements `Camera.setLoopback`
+pub fn set_loopback<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    let this = this.as_camera().unwrap();
+
+    this.set_loopback(args.get_bool(0));
+    Ok(Value::Undefined)
+}
+
+/// Implements `Camera.getModes`
+pub fn get_modes<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    let this = this.as_camera().unwrap();
+
+    let modes = match this.handle() {
+        Some(handle) => activation.context.camera.supported_modes(handle),
+        None => Vec::new(),
+    };
+    let fps = AvmString::new_utf8(activation.gc(), "fps");
+    let mut storage = ArrayStorage::new(modes.len());
+    for mode in modes {
+        let object = ScriptObject::new_object(activation);
+        object.set_string_property_local(istr!("width"), mode.width.into(), activation)?;
+        object.set_string_property_local(istr!("height"), mode.height.into(), activation)?;
+        object.set_string_property_local(fps, mode.fps.into(), activation)?;
+        storage.push(object.into());
+    }
+
+    Ok(ArrayObject::from_storage(activation, storage).into())
+}
+
+/// Implements `Camera.setMode`
+pub fn set_mode<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    let this = this.as_camera().unwrap();
+
+    // Nonsensical values fall back to the default mode of Flash's cameras.
+    let width = args.get_i32(activation, 0)?;
+    let height = args.get_i32(activation, 1)?;
+    let fps = args.get_f64(activation, 2)?;
+    let requested = CameraMode {
+        width: if width > 0 { width as u32 } else { 160 },
+        height: if height > 0 { height as u32 } else { 120 },
+        fps: if fps > 0.0 { fps } else { 15.0 },
+    };
+    let favor_area = args.get_bool(3);
+
+    let Some(handle) = this.handle() else {
+        return Ok(Value::Undefined);
+    };
+    let modes = activation.context.camera.supported_modes(handle);
+    if modes.is_empty() {
+        // Some devices don't list any modes, treat them as only supporting their
+        // current format and keep capturing in that.
+        if let Some(format) = activation.context.camera.format(handle) {
+            Cameras::set_format(this, format);
+        }
+        return Ok(Value::Undefined);
+    }
+    let Some(mode) = select_mode(&modes, requested, favor_area) else {
+        return Ok(Value::Undefined);
+    };
+    activation
+        .context
+        .camera
+        .set_preferred_pixel_format(handle, this.preferred_pixel_format());
+    if let Err(e) = activation.context.camera.set_mode(handle, mode) {
+        tracing::warn!("Failed to set camera mode {mode:?}: {e}");
+        return Ok(Value::Undefined);
+    }
+
+    this.set_fps(mode.fps);
+    if let Some(format) = activation.context.camera.format(handle) {
+        Cameras::set_format(this, format);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Camera.names`
+pub fn get_names<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let devices = Cameras::list_devices(activation.context);
+    Ok(device_names(activation, &devices, |device| &device.name).into())
+}
+
+/// Implements `Camera.isSupported`
+pub fn get_is_supported<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok((!Cameras::list_devices(activation.context).is_empty()).into())
+}
+
+/// Implements `Camera.name`
+pub fn get_name<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    let this = this.as_camera().unwrap();
+
+    Ok(AvmString::new_utf8(activation.gc(), this.name().as_str()).into())
+}
+
+/// Implements `Camera.index`
+pub fn get_index<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    let this = this.as_camera().unwrap();
+
+    // Cameras that aren't backed by a device don't appear in `Camera.names`.
+    Ok(this.index().map_or(-1, |index| index as i32).into())
+}
+
+/// Implements `Camera.activityLevel`
+pub fn get_activity_level<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    let this = this.as_camera().unwrap();
+
+    // Like in Flash Player, cameras that aren't capturing don't measure any activity.
+    if this.handle().is_none() {
+        return Ok((-1).into());
+    }
+    Ok(this.activity_level().into())
+}
+
+/// Implements `Camera.motionLevel`
+pub fn get_motion_level<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    let this = this.as_camera().unwrap();
+
+    Ok(this.motion_level().into())
+}
+
+/// Implements `Camera.motionTimeout`
+pub fn get_motion_timeout<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    let this = this.as_camera().unwrap();
+
+    Ok(this.motion_timeout().into())
+}
+
+/// Implements `Camera.bandwidth`
+pub fn get_bandwidth<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    let this = this.as_camera().unwrap();
+
+    Ok(this.bandwidth().into())
+}
+
+/// Implements `Camera.quality`
+pub fn get_quality<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    let this = this.as_camera().unwrap();
+
+    Ok(this.quality().into())
+}
+
+/// Implements `Camera.keyFrameInterval`
+pub fn get_key_frame_interval<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    let this = this.as_camera().unwrap();
+
+    Ok(this.key_frame_interval().into())
+}
+
+/// Implements `Camera.muted`
+pub fn get_muted<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    let this = this.as_camera().unwrap();
+
+    Ok(this.muted().into())
+}
+
+/// Implements `Camera.loopback`
+pub fn get_loopback<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    let this = this.as_camera().unwrap();
+
+    Ok(this.loopback().into())
+}
+
+/// Implements `Camera.mirrored`
+pub fn get_mirrored<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    let this = this.as_camera().unwrap();
+
+    Ok(this.mirrored().into())
+}
+
+/// Implements `Camera.mirrored`
+pub fn set_mirrored<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    let this = this.as_camera().unwrap();
+
+    this.set_mirrored(args.get_bool(0));
+    Ok(Value::Undefined)
+}
+
+/// Implements `Camera.width`
+pub fn get_width<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    let this = this.as_camera().unwrap();
+
+    Ok(this.format().width.into())
+}
+
+/// Implements `Camera.fps`
+pub fn get_fps<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    let this = this.as_camera().unwrap();
+
+    if this.handle().is_none() {
+        return Ok(0.into());
+    }
+    Ok(this.fps().into())
+}
+
+/// Implements `Camera.currentFPS`
+pub fn get_current_fps<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    let this = this.as_camera().unwrap();
+
+    let Some(handle) = this.handle() else {
+        return Ok(0.into());
+    };
+    Ok(activation.context.camera.current_fps(handle).into())
+}
+
+/// Implements `Camera.height`
+pub fn get_height<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    let this = this.as_camera().unwrap();
+
+    Ok(this.format().height.into())
+}
@@ -0,0 +1,44 @@
+//! Device lookup shared by `flash.media.Camera` and `flash.media.Microphone`
+
+use crate::avm2::activation::Activation;
+use crate::avm2::object::ArrayObject;
+use crate::string::AvmString;
+
+/// Creates the array of device names that `Camera.names` and `Microphone.names` return.
+pub fn device_names<'gc, T>(
+    activation: &mut Activation<'_, 'gc>,
+    devices: &[T],
+    name_of: impl Fn(&T) -> &str,
+) -> ArrayObject<'gc> {
+    let mc = activation.gc();
+    let names = devices
+        .iter()
+        .map(|device| AvmString::new_utf8(mc, name_of(device)));
+
+    ArrayObject::from_string_iter(activation, names)
+}
+
+/// Finds the device that a `name` argument refers to, along with its index.
+///
+/// The name is the index of the device in `names`, not its actual name. Some movies
+/// pass the name itself though, so this falls back to matching on that. Without a
+/// name, or with an empty one, the first device is used.
+pub fn select_device<'a, T>(
+    devices: &'a [T],
+    name: Option<AvmString<'_>>,
+    name_of: impl Fn(&T) -> &str,
+) -> Option<(usize, &'a T)> {
+    let index = match name {
+        Some(name) if name.is_empty() => 0,
+        Some(name) => match name.parse::<usize>() {
+            Ok(index) => index,
+            Err(_) => {
+                let name = name.to_utf8_lossy();
+                devices.iter().position(|device| name_of(device) == name)?
+            }
+        },
+        None => 0,
+    };
+
+    Some((index, devices.get(index)?))
+}
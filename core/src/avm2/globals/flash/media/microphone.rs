@@ -0,0 +1,122 @@
+//! `flash.media.Microphone` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::globals::flash::media::devices::device_names;
+use crate::avm2::globals::slots::flash_media_microphone as microphone_slots;
+use crate::avm2::object::TObject;
+use crate::avm2::parameters::ParametersExt;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::backend::camera::CameraPermission;
+use crate::backend::microphone::{rate_to_hz, FLASH_RATES};
+use crate::microphone::Microphones;
+use crate::string::AvmString;
+
+/// Implements `Microphone.createMicrophone`
+pub fn create_microphone<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    let index = args.get_i32(activation, 0)?;
+    let devices = Microphones::list_devices(activation.context);
+    let Some(device) = usize::try_from(index)
+        .ok()
+        .and_then(|index| devices.get(index))
+    else {
+        return Ok(Value::Null);
+    };
+
+    let class = this
+        .as_class_object()
+        .expect("createMicrophone should be called on the Microphone class");
+    let microphone = class.construct(activation, &[])?;
+    let microphone = microphone.as_object().unwrap();
+    microphone.set_slot(microphone_slots::_INDEX, index.into(), activation)?;
+    let name = AvmString::new_utf8(activation.gc(), &device.name);
+    microphone.set_slot(microphone_slots::_NAME, name.into(), activation)?;
+    microphone.set_slot(microphone_slots::_DEVICE_ID, device.id.into(), activation)?;
+
+    Ok(microphone.into())
+}
+
+/// Implements `Microphone.startCapture`
+pub fn start_capture<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    let device_id = this
+        .get_slot(microphone_slots::_DEVICE_ID)
+        .coerce_to_i32(activation)?;
+    let rate = this
+        .get_slot(microphone_slots::_RATE)
+        .coerce_to_i32(activation)?;
+    if let Ok(device_id) = u32::try_from(device_id) {
+        let sample_rate = rate_to_hz(rate as u32);
+        Microphones::start_capture(activation.context, this, device_id, sample_rate);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Microphone.rate`'s setter
+pub fn set_rate<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    let rate = args.get_i32(activation, 0)?;
+    let device_id = this
+        .get_slot(microphone_slots::_DEVICE_ID)
+        .coerce_to_i32(activation)?;
+    let supported_rates = match u32::try_from(device_id) {
+        Ok(device_id) => activation.context.microphone.supported_rates(device_id),
+        Err(_) => FLASH_RATES.to_vec(),
+    };
+
+    if let Some(rate) = supported_rates
+        .into_iter()
+        .min_by_key(|supported| (i64::from(*supported) - i64::from(rate)).abs())
+    {
+        this.set_slot(microphone_slots::_RATE, rate.into(), activation)?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Microphone.muted`
+///
+/// Like in Flash Player, the privacy settings for cameras also apply to microphones.
+pub fn get_muted<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok((activation.context.camera_permission != CameraPermission::Granted).into())
+}
+
+/// Implements `Microphone.names`
+pub fn get_names<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let devices = Microphones::list_devices(activation.context);
+    Ok(device_names(activation, &devices, |device| &device.name).into())
+}
+
+/// Implements `Microphone.isSupported`
+pub fn get_is_supported<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok((!Microphones::list_devices(activation.context).is_empty()).into())
+}
@@ -62,6 +62,28 @@ pub fn init<'gc>(
     Ok(Value::Undefined)
 }
 
+pub fn attach_camera<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    if let Some(video) = this.as_display_object().and_then(|dobj| dobj.as_video()) {
+        let camera = args
+            .try_get_object(activation, 0)
+            .map(|o| o.as_camera().unwrap());
+
+        if let Some(camera) = camera {
+            video.attach_camera(activation.context, camera);
+        } else {
+            video.detach(activation.context);
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
 pub fn attach_net_stream<'gc>(
     activation: &mut Activation<'_, 'gc>,
     this: Value<'gc>,
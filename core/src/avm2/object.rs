@@ -29,6 +29,7 @@ use std::hash::{Hash, Hasher};
 mod array_object;
 mod bitmapdata_object;
 mod bytearray_object;
+mod camera_object;
 mod class_object;
 mod context3d_object;
 mod date_object;
@@ -75,6 +76,7 @@ pub use crate::avm2::object::bitmapdata_object::{
 pub use crate::avm2::object::bytearray_object::{
     byte_array_allocator, ByteArrayObject, ByteArrayObjectWeak,
 };
+pub use crate::avm2::object::camera_object::{camera_allocator, CameraObject, CameraObjectWeak};
 pub use crate::avm2::object::class_object::{ClassObject, ClassObjectWeak};
 pub use crate::avm2::object::context3d_object::{Context3DObject, Context3DObjectWeak};
 pub use crate::avm2::object::date_object::{date_allocator, DateObject, DateObjectWeak};
@@ -204,6 +206,7 @@ use crate::font::Font;
         SharedObjectObject(SharedObjectObject<'gc>),
         SoundTransformObject(SoundTransformObject<'gc>),
         StyleSheetObject(StyleSheetObject<'gc>),
+        CameraObject(CameraObject<'gc>),
     }
 )]
 pub trait TObject<'gc>: 'gc + Collect<'gc> + Debug + Into<Object<'gc>> + Clone + Copy {
@@ -910,6 +913,10 @@ pub trait TObject<'gc>: 'gc + Collect<'gc> + Debug + Into<Object<'gc>> + Clone +
     fn as_style_sheet(&self) -> Option<StyleSheetObject<'gc>> {
         None
     }
+
+    fn as_camera(&self) -> Option<CameraObject<'gc>> {
+        None
+    }
 }
 
 pub enum ObjectPtr {}
@@ -963,6 +970,7 @@ impl<'gc> Object<'gc> {
             Self::SharedObjectObject(o) => WeakObject::SharedObjectObject(SharedObjectObjectWeak(Gc::downgrade(o.0))),
             Self::SoundTransformObject(o) => WeakObject::SoundTransformObject(SoundTransformObjectWeak(Gc::downgrade(o.0))),
             Self::StyleSheetObject(o) => WeakObject::StyleSheetObject(StyleSheetObjectWeak(Gc::downgrade(o.0))),
+            Self::CameraObject(o) => WeakObject::CameraObject(CameraObjectWeak(Gc::downgrade(o.0))),
         }
     }
 }
@@ -1026,6 +1034,7 @@ pub enum WeakObject<'gc> {
     SharedObjectObject(SharedObjectObjectWeak<'gc>),
     SoundTransformObject(SoundTransformObjectWeak<'gc>),
     StyleSheetObject(StyleSheetObjectWeak<'gc>),
+    CameraObject(CameraObjectWeak<'gc>),
 }
 
 impl<'gc> WeakObject<'gc> {
@@ -1072,6 +1081,7 @@ impl<'gc> WeakObject<'gc> {
             Self::SharedObjectObject(o) => GcWeak::as_ptr(o.0) as *const ObjectPtr,
             Self::SoundTransformObject(o) => GcWeak::as_ptr(o.0) as *const ObjectPtr,
             Self::StyleSheetObject(o) => GcWeak::as_ptr(o.0) as *const ObjectPtr,
+            Self::CameraObject(o) => GcWeak::as_ptr(o.0) as *const ObjectPtr,
         }
     }
 
@@ -1118,6 +1128,7 @@ impl<'gc> WeakObject<'gc> {
             Self::SharedObjectObject(o) => SharedObjectObject(o.0.upgrade(mc)?).into(),
             Self::SoundTransformObject(o) => SoundTransformObject(o.0.upgrade(mc)?).into(),
             Self::StyleSheetObject(o) => StyleSheetObject(o.0.upgrade(mc)?).into(),
+            Self::CameraObject(o) => CameraObject(o.0.upgrade(mc)?).into(),
         })
     }
 }
@@ -84,6 +84,15 @@ impl<'gc> ArrayObject<'gc> {
         ))
     }
 
+    /// Build an array object holding the given strings, in order.
+    pub fn from_string_iter(
+        activation: &mut Activation<'_, 'gc>,
+        strings: impl IntoIterator<Item = AvmString<'gc>>,
+    ) -> ArrayObject<'gc> {
+        let storage = strings.into_iter().collect();
+        Self::from_storage(activation, storage)
+    }
+
     pub fn array_storage(&self) -> Ref<ArrayStorage<'gc>> {
         self.0.array.borrow()
     }
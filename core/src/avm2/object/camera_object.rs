@@ -0,0 +1,385 @@
+//! Object representation for cameras
+
+use crate::avm2::activation::Activation;
+use crate::avm2::object::script_object::ScriptObjectData;
+use crate::avm2::object::{ClassObject, Object, ObjectPtr, TObject};
+use crate::avm2::Error;
+use crate::backend::camera::{CameraFormat, CameraHandle, CameraPixelFormat};
+use crate::camera::ActivityDetector;
+use crate::utils::HasPrefixField;
+use core::fmt;
+use gc_arena::{Collect, Gc, GcWeak, Mutation};
+use ruffle_render::bitmap::BitmapInfo;
+use std::cell::{Cell, Ref, RefCell};
+use std::time::Duration;
+use web_time::Instant;
+
+/// A class instance allocator that allocates Camera objects.
+pub fn camera_allocator<'gc>(
+    class: ClassObject<'gc>,
+    activation: &mut Activation<'_, 'gc>,
+) -> Result<Object<'gc>, Error<'gc>> {
+    let base = ScriptObjectData::new(class);
+
+    Ok(CameraObject(Gc::new(
+        activation.gc(),
+        CameraObjectData {
+            base,
+            handle: Cell::new(None),
+            device_id: Cell::new(0),
+            index: Cell::new(None),
+            name: RefCell::new(String::new()),
+            muted: Cell::new(false),
+            format: Cell::new(CameraFormat::default()),
+            fps: Cell::new(15.0),
+            frozen: Cell::new(false),
+            mirrored: Cell::new(false),
+            loopback: Cell::new(false),
+            cursor: Cell::new(false),
+            preferred_pixel_format: Cell::new(CameraPixelFormat::Rgba),
+            pixel_format: Cell::new(CameraPixelFormat::Rgba),
+            frame: RefCell::new(None),
+            pixels: RefCell::new(None),
+            motion_level: Cell::new(50),
+            motion_timeout: Cell::new(2000),
+            bandwidth: Cell::new(16384),
+            quality: Cell::new(0),
+            key_frame_interval: Cell::new(15),
+            activity_level: Cell::new(0.0),
+            activity: RefCell::new(ActivityDetector::default()),
+        },
+    ))
+    .into())
+}
+
+#[derive(Clone, Collect, Copy)]
+#[collect(no_drop)]
+pub struct CameraObject<'gc>(pub Gc<'gc, CameraObjectData<'gc>>);
+
+#[derive(Clone, Collect, Copy, Debug)]
+#[collect(no_drop)]
+pub struct CameraObjectWeak<'gc>(pub GcWeak<'gc, CameraObjectData<'gc>>);
+
+impl<'gc> CameraObjectWeak<'gc> {
+    pub fn upgrade(self, mc: &Mutation<'gc>) -> Option<CameraObject<'gc>> {
+        self.0.upgrade(mc).map(CameraObject)
+    }
+
+    /// Whether this is a reference to the given camera.
+    pub fn is(self, camera: CameraObject<'gc>) -> bool {
+        std::ptr::eq(self.0.as_ptr(), Gc::as_ptr(camera.0))
+    }
+
+    /// Whether the camera was collected.
+    pub fn is_dropped(self) -> bool {
+        self.0.is_dropped()
+    }
+}
+
+impl fmt::Debug for CameraObject<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CameraObject")
+            .field("ptr", &Gc::as_ptr(self.0))
+            .finish()
+    }
+}
+
+#[derive(Collect, HasPrefixField)]
+#[collect(no_drop)]
+#[repr(C, align(8))]
+pub struct CameraObjectData<'gc> {
+    /// Base script object
+    base: ScriptObjectData<'gc>,
+
+    /// The backend handle of the device this camera captures from.
+    handle: Cell<Option<CameraHandle>>,
+
+    /// The backend id of the device this camera captures from.
+    device_id: Cell<u32>,
+
+    /// The position of the device in `Camera.names`, or `None` if this camera
+    /// wasn't created by `Camera.getCamera`.
+    index: Cell<Option<u32>>,
+
+    /// The name of the device this camera captures from, as reported by the backend.
+    ///
+    /// Empty if this camera wasn't created by `Camera.getCamera`.
+    name: RefCell<String>,
+
+    /// Whether access to the device was denied.
+    muted: Cell<bool>,
+
+    /// The format of the frames currently delivered by the device.
+    format: Cell<CameraFormat>,
+
+    /// The frame rate of the mode selected by `Camera.setMode`.
+    fps: Cell<f64>,
+
+    /// Whether the preview is frozen on the last captured frame.
+    frozen: Cell<bool>,
+
+    /// Whether captured frames are flipped horizontally, like a mirror.
+    mirrored: Cell<bool>,
+
+    /// Whether a local preview should show the video as it's sent out, set by
+    /// `Camera.setLoopback`.
+    ///
+    /// Nothing is encoded yet, so this doesn't change what is displayed.
+    loopback: Cell<bool>,
+
+    /// Whether the system cursor should be captured, set by `Camera.setCursor`.
+    ///
+    /// Only screen capture devices could honour this, so it's just remembered.
+    cursor: Cell<bool>,
+
+    /// The pixel format to ask the device for when `Camera.setMode` switches its mode.
+    ///
+    /// This isn't exposed to ActionScript, it's there for code that would rather
+    /// convert the raw frames itself.
+    preferred_pixel_format: Cell<CameraPixelFormat>,
+
+    /// The pixel format that the device delivered the most recent frame in, before it
+    /// was converted to RGBA for `frame` and `pixels`.
+    pixel_format: Cell<CameraPixelFormat>,
+
+    /// The texture holding the most recently captured frame.
+    frame: RefCell<Option<BitmapInfo>>,
+
+    /// The RGBA pixels of the most recently captured frame, as uploaded to `frame`.
+    pixels: RefCell<Option<Vec<u8>>>,
+
+    /// The activity level that counts as motion, set by `Camera.setMotionLevel`.
+    motion_level: Cell<i32>,
+
+    /// How many milliseconds without motion it takes to become inactive.
+    motion_timeout: Cell<i32>,
+
+    /// The bytes per second that outgoing video may use, set by `Camera.setQuality`.
+    ///
+    /// 0 means as much as needed to keep up `quality`.
+    bandwidth: Cell<i32>,
+
+    /// The picture quality of outgoing video, from 1 to 100.
+    ///
+    /// 0 means that quality may vary to stay within `bandwidth`.
+    quality: Cell<i32>,
+
+    /// How many frames outgoing video has between keyframes.
+    key_frame_interval: Cell<i32>,
+
+    /// The percentage of pixels that changed in the most recently captured frame.
+    activity_level: Cell<f64>,
+
+    #[collect(require_static)]
+    activity: RefCell<ActivityDetector>,
+}
+
+impl<'gc> CameraObject<'gc> {
+    pub fn downgrade(self) -> CameraObjectWeak<'gc> {
+        CameraObjectWeak(Gc::downgrade(self.0))
+    }
+}
+
+impl CameraObject<'_> {
+    pub fn handle(self) -> Option<CameraHandle> {
+        self.0.handle.get()
+    }
+
+    pub fn set_handle(self, handle: Option<CameraHandle>) {
+        self.0.handle.set(handle);
+    }
+
+    pub fn device_id(self) -> u32 {
+        self.0.device_id.get()
+    }
+
+    pub fn set_device_id(self, device_id: u32) {
+        self.0.device_id.set(device_id);
+    }
+
+    pub fn index(self) -> Option<u32> {
+        self.0.index.get()
+    }
+
+    pub fn set_index(self, index: u32) {
+        self.0.index.set(Some(index));
+    }
+
+    pub fn name(&self) -> Ref<'_, String> {
+        self.0.name.borrow()
+    }
+
+    pub fn set_name(self, name: String) {
+        self.0.name.replace(name);
+    }
+
+    pub fn muted(self) -> bool {
+        self.0.muted.get()
+    }
+
+    pub fn set_muted(self, muted: bool) {
+        self.0.muted.set(muted);
+    }
+
+    pub fn format(self) -> CameraFormat {
+        self.0.format.get()
+    }
+
+    pub fn set_format(self, format: CameraFormat) {
+        self.0.format.set(format);
+    }
+
+    pub fn fps(self) -> f64 {
+        self.0.fps.get()
+    }
+
+    pub fn set_fps(self, fps: f64) {
+        self.0.fps.set(fps);
+    }
+
+    pub fn frozen(self) -> bool {
+        self.0.frozen.get()
+    }
+
+    pub fn set_frozen(self, frozen: bool) {
+        self.0.frozen.set(frozen);
+    }
+
+    pub fn loopback(self) -> bool {
+        self.0.loopback.get()
+    }
+
+    pub fn set_loopback(self, loopback: bool) {
+        self.0.loopback.set(loopback);
+    }
+
+    pub fn cursor(self) -> bool {
+        self.0.cursor.get()
+    }
+
+    pub fn set_cursor(self, cursor: bool) {
+        self.0.cursor.set(cursor);
+    }
+
+    pub fn preferred_pixel_format(self) -> CameraPixelFormat {
+        self.0.preferred_pixel_format.get()
+    }
+
+    pub fn set_preferred_pixel_format(self, pixel_format: CameraPixelFormat) {
+        self.0.preferred_pixel_format.set(pixel_format);
+    }
+
+    pub fn pixel_format(self) -> CameraPixelFormat {
+        self.0.pixel_format.get()
+    }
+
+    pub fn set_pixel_format(self, pixel_format: CameraPixelFormat) {
+        self.0.pixel_format.set(pixel_format);
+    }
+
+    pub fn mirrored(self) -> bool {
+        self.0.mirrored.get()
+    }
+
+    pub fn set_mirrored(self, mirrored: bool) {
+        self.0.mirrored.set(mirrored);
+    }
+
+    /// The texture holding the most recently captured frame, if any frame arrived yet.
+    pub fn frame(self) -> Option<BitmapInfo> {
+        self.0.frame.borrow().clone()
+    }
+
+    /// Sets the texture holding the most recently captured frame.
+    ///
+    /// Dropping the texture drops the pixels of the frame as well.
+    pub fn set_frame(self, frame: Option<BitmapInfo>) {
+        if frame.is_none() {
+            self.0.pixels.replace(None);
+        }
+        self.0.frame.replace(frame);
+    }
+
+    /// The RGBA pixels of the most recently captured frame, without copying them.
+    pub fn pixels(self) -> Ref<'gc, Option<Vec<u8>>> {
+        Gc::as_ref(self.0).pixels.borrow()
+    }
+
+    pub fn set_pixels(self, pixels: Vec<u8>) {
+        self.0.pixels.replace(Some(pixels));
+    }
+
+    pub fn motion_level(self) -> i32 {
+        self.0.motion_level.get()
+    }
+
+    pub fn set_motion_level(self, motion_level: i32) {
+        self.0.motion_level.set(motion_level);
+    }
+
+    pub fn motion_timeout(self) -> i32 {
+        self.0.motion_timeout.get()
+    }
+
+    pub fn set_motion_timeout(self, motion_timeout: i32) {
+        self.0.motion_timeout.set(motion_timeout);
+    }
+
+    pub fn bandwidth(self) -> i32 {
+        self.0.bandwidth.get()
+    }
+
+    pub fn set_bandwidth(self, bandwidth: i32) {
+        self.0.bandwidth.set(bandwidth);
+    }
+
+    pub fn quality(self) -> i32 {
+        self.0.quality.get()
+    }
+
+    pub fn set_quality(self, quality: i32) {
+        self.0.quality.set(quality);
+    }
+
+    pub fn key_frame_interval(self) -> i32 {
+        self.0.key_frame_interval.get()
+    }
+
+    pub fn set_key_frame_interval(self, key_frame_interval: i32) {
+        self.0.key_frame_interval.set(key_frame_interval);
+    }
+
+    pub fn activity_level(self) -> f64 {
+        self.0.activity_level.get()
+    }
+
+    /// Measures the motion in a newly captured frame, returning the new activity level.
+    pub fn measure_activity(self, rgba: &[u8]) -> f64 {
+        let activity_level = self.0.activity.borrow_mut().measure(rgba);
+        self.0.activity_level.set(activity_level);
+        activity_level
+    }
+
+    /// Updates whether the camera is active, returning the new state if it changed.
+    pub fn update_activity(self, activity_level: Option<f64>, now: Instant) -> Option<bool> {
+        let timeout = Duration::from_millis(self.motion_timeout() as u64);
+        self.0
+            .activity
+            .borrow_mut()
+            .update(activity_level, self.motion_level(), timeout, now)
+    }
+}
+
+impl<'gc> TObject<'gc> for CameraObject<'gc> {
+    fn gc_base(&self) -> Gc<'gc, ScriptObjectData<'gc>> {
+        HasPrefixField::as_prefix_gc(self.0)
+    }
+
+    fn as_ptr(&self) -> *const ObjectPtr {
+        Gc::as_ptr(self.0) as *const ObjectPtr
+    }
+
+    fn as_camera(&self) -> Option<CameraObject<'gc>> {
+        Some(*self)
+    }
+}
@@ -0,0 +1,17 @@
+use crate::avm2::{Activation, Error};
+use crate::player::PlayerBuilder;
+
+pub fn with_avm2<F>(builder: PlayerBuilder, test: F)
+where
+    F: for<'a, 'gc> FnOnce(&mut Activation<'_, 'gc>) -> Result<(), Error<'gc>>,
+{
+    let player = builder.build();
+    let mut player = player.lock().unwrap();
+    player.mutate_with_update_context(|context| {
+        let mut activation = Activation::from_nothing(context);
+        let result = test(&mut activation);
+        if let Err(e) = result {
+            panic!("Encountered exception during test: {e:?}");
+        }
+    })
+}
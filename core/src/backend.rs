@@ -1,5 +1,7 @@
 pub mod audio;
+pub mod camera;
 pub mod log;
+pub mod microphone;
 pub mod navigator;
 pub mod storage;
 pub mod ui;
@@ -0,0 +1,615 @@
+use slotmap::{new_key_type, SlotMap};
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
+use thiserror::Error;
+
+#[cfg(feature = "mock_camera")]
+mod mock;
+
+#[cfg(feature = "mock_camera")]
+pub use mock::{MockCameraBackend, MockCameraDevice};
+
+new_key_type! {
+    /// A handle to a camera device that was opened by a `CameraBackend`.
+    pub struct CameraHandle;
+}
+
+/// A video capture device that the backend is able to open.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CameraDevice {
+    /// The backend-specific identifier of this device, as passed to `CameraBackend::open`.
+    pub id: u32,
+
+    /// The human-readable name of this device, as reported by `Camera.names`.
+    pub name: String,
+}
+
+impl CameraDevice {
+    /// Creates a device from a name as reported by the OS, which may be NUL-padded
+    /// and isn't guaranteed to be valid UTF-8.
+    ///
+    /// Invalid bytes are replaced with U+FFFD, which is also how the names passed to
+    /// `Camera.getCamera` are normalized, so the device stays selectable by its name.
+    pub fn from_raw_name(id: u32, name: &[u8]) -> Self {
+        let end = name.iter().position(|&b| b == 0).unwrap_or(name.len());
+        Self {
+            id,
+            name: String::from_utf8_lossy(&name[..end]).trim().to_string(),
+        }
+    }
+}
+
+/// The dimensions of the frames delivered by an open camera.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CameraFormat {
+    pub width: u32,
+    pub height: u32,
+
+    /// The width and height of a single pixel, as a ratio. Square pixels are `(1, 1)`.
+    pub pixel_aspect_ratio: (u32, u32),
+}
+
+impl CameraFormat {
+    /// A format with square pixels.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            pixel_aspect_ratio: (1, 1),
+        }
+    }
+
+    /// The size that frames should be displayed at so that they don't look squashed.
+    ///
+    /// Non-square pixels are corrected by stretching the width, so the height always
+    /// matches the captured height.
+    pub fn display_size(&self) -> (u32, u32) {
+        let (numerator, denominator) = self.pixel_aspect_ratio;
+        if numerator == 0 || denominator == 0 {
+            return (self.width, self.height);
+        }
+        let width = u64::from(self.width) * u64::from(numerator) / u64::from(denominator);
+        (width.try_into().unwrap_or(u32::MAX), self.height)
+    }
+}
+
+impl Default for CameraFormat {
+    fn default() -> Self {
+        Self::new(0, 0)
+    }
+}
+
+/// A combination of frame size and frame rate that a device can capture in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CameraMode {
+    pub width: u32,
+    pub height: u32,
+    pub fps: f64,
+}
+
+/// The layout of the pixels in a `CameraFrame`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CameraPixelFormat {
+    /// 8 bits per channel RGBA, which is what the player displays.
+    #[default]
+    Rgba,
+
+    /// Packed YUV 4:2:2, with two pixels sharing their chroma in every 4 bytes.
+    Yuyv,
+}
+
+/// A single captured frame.
+#[derive(Clone, Debug)]
+pub struct CameraFrame {
+    pub format: CameraFormat,
+
+    /// The layout of `data`, which is RGBA unless another format was negotiated with
+    /// `CameraBackend::set_preferred_pixel_format`.
+    pub pixel_format: CameraPixelFormat,
+
+    pub data: Vec<u8>,
+}
+
+impl CameraFrame {
+    /// A frame of RGBA pixels.
+    pub fn rgba(format: CameraFormat, rgba: Vec<u8>) -> Self {
+        Self {
+            format,
+            pixel_format: CameraPixelFormat::Rgba,
+            data: rgba,
+        }
+    }
+
+    /// Converts this frame into RGBA, which leaves RGBA frames untouched.
+    pub fn into_rgba(self) -> Self {
+        match self.pixel_format {
+            CameraPixelFormat::Rgba => self,
+            CameraPixelFormat::Yuyv => Self::rgba(self.format, yuyv_to_rgba(&self.data)),
+        }
+    }
+}
+
+/// Converts packed YUYV 4:2:2 pixels into RGBA, using the BT.601 coefficients.
+pub fn yuyv_to_rgba(yuyv: &[u8]) -> Vec<u8> {
+    let mut rgba = Vec::with_capacity(yuyv.len() * 2);
+    for chunk in yuyv.chunks_exact(4) {
+        let [y0, u, y1, v] = [chunk[0], chunk[1], chunk[2], chunk[3]];
+        for y in [y0, y1] {
+            let c = 298 * (y as i32 - 16);
+            let d = u as i32 - 128;
+            let e = v as i32 - 128;
+            rgba.extend_from_slice(&[
+                ((c + 409 * e + 128) >> 8).clamp(0, 255) as u8,
+                ((c - 100 * d - 208 * e + 128) >> 8).clamp(0, 255) as u8,
+                ((c + 516 * d + 128) >> 8).clamp(0, 255) as u8,
+                0xFF,
+            ]);
+        }
+    }
+    rgba
+}
+
+/// Notifications from the backend about an open camera.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CameraEvent {
+    /// The device renegotiated its format (e.g. due to autofocus or lighting changes),
+    /// and all frames from now on will be delivered in the given format.
+    FormatChanged(CameraFormat),
+
+    /// The device was unplugged, and won't deliver any more frames.
+    ///
+    /// The handle stays valid until it's closed.
+    Disconnected,
+}
+
+/// Whether the movie may access the user's cameras, as answered in the privacy dialog.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+pub enum CameraPermission {
+    Granted,
+    Denied,
+
+    /// The user hasn't answered yet, so cameras stay muted until
+    /// `Player::set_camera_permission` resolves it.
+    ///
+    /// Movies may only capture once a frontend has asked the user, so this is the default.
+    #[default]
+    Prompt,
+}
+
+/// Whether the movie may capture from a particular device, as answered by
+/// `UiBackend::request_camera_access`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CameraAccess {
+    /// The device may be captured from, but the user is asked again the next time
+    /// it's opened.
+    Allow,
+
+    /// The device may not be captured from, but the user is asked again the next time
+    /// it would be opened.
+    Deny,
+
+    /// The device may be captured from for the rest of the session.
+    AlwaysAllow,
+
+    /// The device may not be captured from for the rest of the session.
+    AlwaysDeny,
+}
+
+#[derive(Debug, Error)]
+pub enum CameraError {
+    #[error("Camera device not found")]
+    NotFound,
+
+    #[error("Camera device is busy")]
+    Busy,
+
+    #[error("Access to the camera device was denied")]
+    PermissionDenied,
+
+    #[error("Capturing from camera devices is not supported")]
+    Unsupported,
+
+    #[error("Camera device error: {0}")]
+    Other(String),
+}
+
+pub trait CameraBackend {
+    /// Lists the video capture devices available to the player.
+    fn list_devices(&mut self) -> Vec<CameraDevice>;
+
+    /// Opens the device with the given id and starts capturing frames from it.
+    fn open(&mut self, id: u32) -> Result<CameraHandle, CameraError>;
+
+    /// Stops capturing from a device opened by `open`.
+    fn close(&mut self, handle: CameraHandle);
+
+    /// The format of the frames that the device is currently delivering.
+    fn format(&self, handle: CameraHandle) -> Option<CameraFormat>;
+
+    /// The capture modes that an open device supports.
+    fn supported_modes(&self, handle: CameraHandle) -> Vec<CameraMode>;
+
+    /// Switches an open device to one of its `supported_modes`.
+    ///
+    /// The new format takes effect immediately, so no `FormatChanged` event is sent for it.
+    fn set_mode(&mut self, handle: CameraHandle, mode: CameraMode) -> Result<(), CameraError>;
+
+    /// Asks an open device to deliver its frames in another pixel format, starting
+    /// with the next `set_mode`.
+    ///
+    /// This is only a preference: devices that can't capture in the format keep
+    /// delivering RGBA, and every frame says which format it's in.
+    fn set_preferred_pixel_format(
+        &mut self,
+        _handle: CameraHandle,
+        _pixel_format: CameraPixelFormat,
+    ) {
+    }
+
+    /// Freezes or resumes an open device.
+    ///
+    /// A frozen device stays open, but doesn't deliver any frames until it's resumed.
+    fn set_frozen(&mut self, handle: CameraHandle, frozen: bool);
+
+    /// The number of frames that an open device captured during the last second.
+    fn current_fps(&self, handle: CameraHandle) -> f64;
+
+    /// Takes the next pending event of an open device.
+    ///
+    /// The player drains all events before asking for a new frame, so a `FormatChanged`
+    /// event is always seen before the first frame in the new format.
+    fn poll_event(&mut self, handle: CameraHandle) -> Option<CameraEvent>;
+
+    /// Takes the most recently captured frame, if one arrived since the last call.
+    fn next_frame(&mut self, handle: CameraHandle) -> Option<CameraFrame>;
+}
+
+/// Camera backend that doesn't have any devices.
+pub struct NullCameraBackend {}
+
+impl NullCameraBackend {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl CameraBackend for NullCameraBackend {
+    fn list_devices(&mut self) -> Vec<CameraDevice> {
+        Vec::new()
+    }
+
+    fn open(&mut self, _id: u32) -> Result<CameraHandle, CameraError> {
+        Err(CameraError::NotFound)
+    }
+
+    fn close(&mut self, _handle: CameraHandle) {}
+
+    fn format(&self, _handle: CameraHandle) -> Option<CameraFormat> {
+        None
+    }
+
+    fn supported_modes(&self, _handle: CameraHandle) -> Vec<CameraMode> {
+        Vec::new()
+    }
+
+    fn set_mode(&mut self, _handle: CameraHandle, _mode: CameraMode) -> Result<(), CameraError> {
+        Err(CameraError::NotFound)
+    }
+
+    fn set_frozen(&mut self, _handle: CameraHandle, _frozen: bool) {}
+
+    fn current_fps(&self, _handle: CameraHandle) -> f64 {
+        0.0
+    }
+
+    fn poll_event(&mut self, _handle: CameraHandle) -> Option<CameraEvent> {
+        None
+    }
+
+    fn next_frame(&mut self, _handle: CameraHandle) -> Option<CameraFrame> {
+        None
+    }
+}
+
+impl Default for NullCameraBackend {
+    fn default() -> Self {
+        NullCameraBackend::new()
+    }
+}
+
+/// A device opened through a `SharedCameraBackend`.
+struct SharedDevice {
+    /// The handle of the device in the wrapped backend.
+    handle: CameraHandle,
+
+    /// The number of handles that were given out for this device.
+    consumers: usize,
+}
+
+/// A handle given out by a `SharedCameraBackend`.
+struct Consumer {
+    device_id: u32,
+    events: VecDeque<CameraEvent>,
+    frame: Option<CameraFrame>,
+    frozen: bool,
+}
+
+/// Wraps a backend so that a device is only opened once, no matter how many times
+/// `open` is called for it.
+///
+/// Most platforms only allow a single exclusive open of a camera, so every handle
+/// for the same device shares the one open device instead, and receives copies of
+/// all of its frames. The device is closed once its last handle is closed.
+pub struct SharedCameraBackend<B> {
+    inner: B,
+    devices: HashMap<u32, SharedDevice>,
+    consumers: SlotMap<CameraHandle, Consumer>,
+}
+
+impl<B: CameraBackend> SharedCameraBackend<B> {
+    pub fn new(inner: B) -> Self {
+        Self {
+            inner,
+            devices: HashMap::new(),
+            consumers: SlotMap::with_key(),
+        }
+    }
+
+    pub fn inner(&self) -> &B {
+        &self.inner
+    }
+
+    fn device_handle(&self, handle: CameraHandle) -> Option<CameraHandle> {
+        let consumer = self.consumers.get(handle)?;
+        Some(self.devices.get(&consumer.device_id)?.handle)
+    }
+
+    /// Hands out everything that the device of `handle` delivered since the last call
+    /// to all of its consumers.
+    fn pump(&mut self, handle: CameraHandle) {
+        let Some(device_id) = self.consumers.get(handle).map(|c| c.device_id) else {
+            return;
+        };
+        let Some(device) = self.devices.get(&device_id) else {
+            return;
+        };
+
+        let mut events = Vec::new();
+        while let Some(event) = self.inner.poll_event(device.handle) {
+            events.push(event);
+        }
+        let frame = self.inner.next_frame(device.handle);
+
+        for consumer in self.consumers.values_mut() {
+            if consumer.device_id != device_id {
+                continue;
+            }
+            consumer.events.extend(events.iter().cloned());
+            if let Some(frame) = &frame {
+                consumer.frame = Some(frame.clone());
+            }
+        }
+    }
+}
+
+impl<B: CameraBackend> CameraBackend for SharedCameraBackend<B> {
+    fn list_devices(&mut self) -> Vec<CameraDevice> {
+        self.inner.list_devices()
+    }
+
+    fn open(&mut self, id: u32) -> Result<CameraHandle, CameraError> {
+        match self.devices.entry(id) {
+            Entry::Occupied(mut device) => device.get_mut().consumers += 1,
+            Entry::Vacant(device) => {
+                let handle = self.inner.open(id)?;
+                device.insert(SharedDevice {
+                    handle,
+                    consumers: 1,
+                });
+            }
+        }
+        Ok(self.consumers.insert(Consumer {
+            device_id: id,
+            events: VecDeque::new(),
+            frame: None,
+            frozen: false,
+        }))
+    }
+
+    fn close(&mut self, handle: CameraHandle) {
+        let Some(consumer) = self.consumers.remove(handle) else {
+            return;
+        };
+        if let Entry::Occupied(mut device) = self.devices.entry(consumer.device_id) {
+            device.get_mut().consumers -= 1;
+            if device.get().consumers == 0 {
+                self.inner.close(device.remove().handle);
+            }
+        }
+    }
+
+    fn format(&self, handle: CameraHandle) -> Option<CameraFormat> {
+        self.inner.format(self.device_handle(handle)?)
+    }
+
+    fn supported_modes(&self, handle: CameraHandle) -> Vec<CameraMode> {
+        match self.device_handle(handle) {
+            Some(device) => self.inner.supported_modes(device),
+            None => Vec::new(),
+        }
+    }
+
+    fn set_mode(&mut self, handle: CameraHandle, mode: CameraMode) -> Result<(), CameraError> {
+        let device = self.device_handle(handle).ok_or(CameraError::NotFound)?;
+        self.inner.set_mode(device, mode)?;
+
+        // The caller knows about the new format already, but the other consumers don't.
+        if let Some(format) = self.inner.format(device) {
+            let device_id = self.consumers[handle].device_id;
+            for (other, consumer) in self.consumers.iter_mut() {
+                if other != handle && consumer.device_id == device_id {
+                    consumer
+                        .events
+                        .push_back(CameraEvent::FormatChanged(format));
+                    consumer.frame = None;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn set_preferred_pixel_format(
+        &mut self,
+        handle: CameraHandle,
+        pixel_format: CameraPixelFormat,
+    ) {
+        // The device is shared, so the last preference wins for all of its consumers.
+        if let Some(device) = self.device_handle(handle) {
+            self.inner.set_preferred_pixel_format(device, pixel_format);
+        }
+    }
+
+    fn set_frozen(&mut self, handle: CameraHandle, frozen: bool) {
+        // The device keeps capturing for the other consumers.
+        if let Some(consumer) = self.consumers.get_mut(handle) {
+            consumer.frozen = frozen;
+        }
+    }
+
+    fn current_fps(&self, handle: CameraHandle) -> f64 {
+        match self.device_handle(handle) {
+            Some(device) => self.inner.current_fps(device),
+            None => 0.0,
+        }
+    }
+
+    fn poll_event(&mut self, handle: CameraHandle) -> Option<CameraEvent> {
+        self.pump(handle);
+        self.consumers.get_mut(handle)?.events.pop_front()
+    }
+
+    fn next_frame(&mut self, handle: CameraHandle) -> Option<CameraFrame> {
+        self.pump(handle);
+        let consumer = self.consumers.get_mut(handle)?;
+        if consumer.frozen {
+            return None;
+        }
+        consumer.frame.take()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A single device that delivers numbered frames, and counts how often it's opened.
+    #[derive(Default)]
+    struct CountingCamera {
+        handles: SlotMap<CameraHandle, ()>,
+        opens: usize,
+        closes: usize,
+        frames: u8,
+    }
+
+    impl CameraBackend for CountingCamera {
+        fn list_devices(&mut self) -> Vec<CameraDevice> {
+            vec![CameraDevice {
+                id: 0,
+                name: "Counting Camera".to_string(),
+            }]
+        }
+
+        fn open(&mut self, _id: u32) -> Result<CameraHandle, CameraError> {
+            if !self.handles.is_empty() {
+                return Err(CameraError::Busy);
+            }
+            self.opens += 1;
+            Ok(self.handles.insert(()))
+        }
+
+        fn close(&mut self, handle: CameraHandle) {
+            self.closes += 1;
+            self.handles.remove(handle);
+        }
+
+        fn format(&self, _handle: CameraHandle) -> Option<CameraFormat> {
+            Some(CameraFormat::new(1, 1))
+        }
+
+        fn supported_modes(&self, _handle: CameraHandle) -> Vec<CameraMode> {
+            Vec::new()
+        }
+
+        fn set_mode(
+            &mut self,
+            _handle: CameraHandle,
+            _mode: CameraMode,
+        ) -> Result<(), CameraError> {
+            Err(CameraError::Other("Unsupported".to_string()))
+        }
+
+        fn set_frozen(&mut self, _handle: CameraHandle, _frozen: bool) {}
+
+        fn current_fps(&self, _handle: CameraHandle) -> f64 {
+            0.0
+        }
+
+        fn poll_event(&mut self, _handle: CameraHandle) -> Option<CameraEvent> {
+            None
+        }
+
+        fn next_frame(&mut self, handle: CameraHandle) -> Option<CameraFrame> {
+            self.handles.get(handle)?;
+            self.frames += 1;
+            Some(CameraFrame::rgba(
+                CameraFormat::new(1, 1),
+                vec![self.frames; 4],
+            ))
+        }
+    }
+
+    #[test]
+    fn device_is_opened_once() {
+        let mut backend = SharedCameraBackend::new(CountingCamera::default());
+        let first = backend.open(0).expect("First open should succeed");
+        let second = backend
+            .open(0)
+            .expect("Second open should share the device");
+        assert_ne!(first, second);
+        assert_eq!(backend.inner().opens, 1);
+
+        // Both consumers see the frame, no matter which one asked for it first.
+        let frame = backend.next_frame(first).unwrap();
+        assert_eq!(frame.data, vec![1; 4]);
+        assert_eq!(backend.next_frame(second).unwrap().data, vec![2; 4]);
+        assert_eq!(backend.next_frame(first).unwrap().data, vec![3; 4]);
+
+        backend.close(first);
+        assert_eq!(backend.inner().closes, 0);
+        assert!(backend.next_frame(first).is_none());
+        assert!(backend.next_frame(second).is_some());
+
+        backend.close(second);
+        assert_eq!(backend.inner().closes, 1);
+
+        // The device can be opened again after it was released.
+        backend.open(0).expect("Reopening should succeed");
+        assert_eq!(backend.inner().opens, 2);
+    }
+
+    #[test]
+    fn frames_are_fanned_out() {
+        let mut backend = SharedCameraBackend::new(CountingCamera::default());
+        let first = backend.open(0).unwrap();
+        let second = backend.open(0).unwrap();
+
+        // A frozen consumer doesn't stop the other one from receiving frames.
+        backend.set_frozen(second, true);
+        assert!(backend.next_frame(second).is_none());
+        assert_eq!(backend.next_frame(first).unwrap().data, vec![2; 4]);
+
+        backend.set_frozen(second, false);
+        assert_eq!(backend.next_frame(second).unwrap().data, vec![3; 4]);
+    }
+}
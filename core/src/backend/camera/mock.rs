@@ -0,0 +1,214 @@
+use super::{
+    CameraBackend, CameraDevice, CameraError, CameraEvent, CameraFormat, CameraFrame, CameraHandle,
+    CameraMode,
+};
+use slotmap::SlotMap;
+
+/// A virtual device of a `MockCameraBackend`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct MockCameraDevice {
+    pub name: String,
+    pub width: u32,
+    pub height: u32,
+    pub fps: f64,
+}
+
+impl Default for MockCameraDevice {
+    fn default() -> Self {
+        Self {
+            name: "Mock Camera".to_string(),
+            width: 160,
+            height: 120,
+            fps: 15.0,
+        }
+    }
+}
+
+struct OpenMockCamera {
+    device_id: u32,
+    frozen: bool,
+
+    /// The number of frames generated so far, which the next frame is derived from.
+    frame_number: u32,
+}
+
+/// Camera backend with a fixed set of virtual devices, which deliver a synthetic
+/// frame every time one is requested.
+///
+/// Frames only depend on how many were generated before, so movies see the same
+/// devices and pixels on every run.
+pub struct MockCameraBackend {
+    devices: Vec<MockCameraDevice>,
+    cameras: SlotMap<CameraHandle, OpenMockCamera>,
+}
+
+impl MockCameraBackend {
+    pub fn new(devices: Vec<MockCameraDevice>) -> Self {
+        Self {
+            devices,
+            cameras: SlotMap::with_key(),
+        }
+    }
+
+    fn device(&self, handle: CameraHandle) -> Option<&MockCameraDevice> {
+        let camera = self.cameras.get(handle)?;
+        self.devices.get(camera.device_id as usize)
+    }
+}
+
+impl CameraBackend for MockCameraBackend {
+    fn list_devices(&mut self) -> Vec<CameraDevice> {
+        self.devices
+            .iter()
+            .enumerate()
+            .map(|(id, device)| CameraDevice {
+                id: id as u32,
+                name: device.name.clone(),
+            })
+            .collect()
+    }
+
+    fn open(&mut self, id: u32) -> Result<CameraHandle, CameraError> {
+        if id as usize >= self.devices.len() {
+            return Err(CameraError::NotFound);
+        }
+        Ok(self.cameras.insert(OpenMockCamera {
+            device_id: id,
+            frozen: false,
+            frame_number: 0,
+        }))
+    }
+
+    fn close(&mut self, handle: CameraHandle) {
+        self.cameras.remove(handle);
+    }
+
+    fn format(&self, handle: CameraHandle) -> Option<CameraFormat> {
+        let device = self.device(handle)?;
+        Some(CameraFormat::new(device.width, device.height))
+    }
+
+    fn supported_modes(&self, handle: CameraHandle) -> Vec<CameraMode> {
+        self.device(handle)
+            .map(|device| {
+                vec![CameraMode {
+                    width: device.width,
+                    height: device.height,
+                    fps: device.fps,
+                }]
+            })
+            .unwrap_or_default()
+    }
+
+    fn set_mode(&mut self, handle: CameraHandle, mode: CameraMode) -> Result<(), CameraError> {
+        if self.supported_modes(handle).contains(&mode) {
+            Ok(())
+        } else {
+            Err(CameraError::Other(format!("Unsupported mode {mode:?}")))
+        }
+    }
+
+    fn set_frozen(&mut self, handle: CameraHandle, frozen: bool) {
+        if let Some(camera) = self.cameras.get_mut(handle) {
+            camera.frozen = frozen;
+        }
+    }
+
+    fn current_fps(&self, handle: CameraHandle) -> f64 {
+        match self.cameras.get(handle) {
+            Some(camera) if !camera.frozen => self.device(handle).map_or(0.0, |device| device.fps),
+            _ => 0.0,
+        }
+    }
+
+    fn poll_event(&mut self, _handle: CameraHandle) -> Option<CameraEvent> {
+        None
+    }
+
+    fn next_frame(&mut self, handle: CameraHandle) -> Option<CameraFrame> {
+        let format = self.format(handle)?;
+        let camera = self.cameras.get_mut(handle)?;
+        if camera.frozen {
+            return None;
+        }
+        let frame_number = camera.frame_number;
+        camera.frame_number += 1;
+        Some(CameraFrame::rgba(
+            format,
+            test_pattern(format.width, format.height, frame_number),
+        ))
+    }
+}
+
+/// Generates a gradient that scrolls to the left by one pixel per frame.
+fn test_pattern(width: u32, height: u32, frame_number: u32) -> Vec<u8> {
+    let mut rgba = Vec::with_capacity((width * height * 4) as usize);
+    for y in 0..height {
+        for x in 0..width {
+            rgba.extend_from_slice(&[
+                x.wrapping_add(frame_number) as u8,
+                y as u8,
+                frame_number.wrapping_mul(8) as u8,
+                0xFF,
+            ]);
+        }
+    }
+    rgba
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn backend() -> MockCameraBackend {
+        MockCameraBackend::new(vec![
+            MockCameraDevice::default(),
+            MockCameraDevice {
+                name: "Second Camera".to_string(),
+                width: 4,
+                height: 2,
+                fps: 30.0,
+            },
+        ])
+    }
+
+    #[test]
+    fn devices_are_listed_in_order() {
+        let names: Vec<_> = backend()
+            .list_devices()
+            .into_iter()
+            .map(|device| (device.id, device.name))
+            .collect();
+        assert_eq!(
+            names,
+            [
+                (0, "Mock Camera".to_string()),
+                (1, "Second Camera".to_string())
+            ]
+        );
+        assert!(matches!(backend().open(2), Err(CameraError::NotFound)));
+    }
+
+    #[test]
+    fn frames_are_deterministic() {
+        let mut first = backend();
+        let mut second = backend();
+        let a = first.open(1).unwrap();
+        let b = second.open(1).unwrap();
+        assert_eq!(first.format(a), Some(CameraFormat::new(4, 2)));
+        assert_eq!(first.current_fps(a), 30.0);
+
+        let frame = first.next_frame(a).unwrap();
+        assert_eq!(frame.data.len(), 4 * 2 * 4);
+        assert_eq!(frame.data, second.next_frame(b).unwrap().data);
+
+        // Every frame differs from the one before, so that it registers as motion.
+        assert_ne!(frame.data, first.next_frame(a).unwrap().data);
+
+        first.set_frozen(a, true);
+        assert!(first.next_frame(a).is_none());
+        assert_eq!(first.current_fps(a), 0.0);
+    }
+}
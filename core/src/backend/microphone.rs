@@ -0,0 +1,95 @@
+use slotmap::new_key_type;
+use thiserror::Error;
+
+new_key_type! {
+    /// A handle to a microphone that a `MicrophoneBackend` is capturing from.
+    pub struct MicrophoneHandle;
+}
+
+/// An audio capture device that the backend knows about.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MicrophoneDevice {
+    /// The backend-specific identifier of this device.
+    pub id: u32,
+
+    /// The human-readable name of this device, as reported by `Microphone.names`.
+    pub name: String,
+}
+
+pub trait MicrophoneBackend {
+    /// Lists the devices that can currently be captured from, in the order that
+    /// `Microphone.names` reports them.
+    fn list_devices(&mut self) -> Vec<MicrophoneDevice>;
+
+    /// Lists the sampling rates, in kHz, that the given device can capture at.
+    ///
+    /// This must only contain rates from `FLASH_RATES`.
+    fn supported_rates(&self, _id: u32) -> Vec<u32> {
+        FLASH_RATES.to_vec()
+    }
+
+    /// Starts capturing from a device, at `sample_rate` samples per second.
+    ///
+    /// Capturing must happen in the background, as samples are only picked up
+    /// once per frame by `take_samples`.
+    fn open(&mut self, _id: u32, _sample_rate: u32) -> Result<MicrophoneHandle, MicrophoneError> {
+        Err(MicrophoneError::Unsupported)
+    }
+
+    /// Stops capturing from a device opened by `open`.
+    fn close(&mut self, _handle: MicrophoneHandle) {}
+
+    /// Takes the samples captured since the last call, as mono floats between -1 and 1.
+    fn take_samples(&mut self, _handle: MicrophoneHandle) -> Vec<f32> {
+        Vec::new()
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum MicrophoneError {
+    #[error("Microphone device not found")]
+    NotFound,
+
+    #[error("Capturing audio isn't supported by this backend")]
+    Unsupported,
+
+    #[error("{0}")]
+    Other(String),
+}
+
+/// The number of samples per second that a rate from `FLASH_RATES` stands for.
+///
+/// Flash Player names the rates derived from 44.1 kHz by rounding them down.
+pub fn rate_to_hz(rate: u32) -> u32 {
+    match rate {
+        5 => 5512,
+        11 => 11025,
+        22 => 22050,
+        44 => 44100,
+        _ => rate * 1000,
+    }
+}
+
+/// The sampling rates, in kHz, that `Microphone.rate` can be set to.
+pub const FLASH_RATES: [u32; 5] = [5, 8, 11, 22, 44];
+
+/// Microphone backend that doesn't have any devices.
+pub struct NullMicrophoneBackend {}
+
+impl NullMicrophoneBackend {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl MicrophoneBackend for NullMicrophoneBackend {
+    fn list_devices(&mut self) -> Vec<MicrophoneDevice> {
+        Vec::new()
+    }
+}
+
+impl Default for NullMicrophoneBackend {
+    fn default() -> Self {
+        NullMicrophoneBackend::new()
+    }
+}
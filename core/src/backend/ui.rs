@@ -1,3 +1,4 @@
+use crate::backend::camera::{CameraAccess, CameraDevice};
 pub use crate::loader::Error as DialogLoaderError;
 use crate::{backend::navigator::OwnedFuture, font::FontQuery};
 use chrono::{DateTime, Utc};
@@ -126,6 +127,15 @@ pub trait UiBackend: Any {
 
     /// Mark that any previously open dialog has been closed
     fn close_file_dialog(&mut self);
+
+    /// Asks whether the movie may capture from a camera, right before the device is opened.
+    ///
+    /// This is only asked while the player's `CameraPermission` is granted, and allows
+    /// gating access per device. Every device is denied by default, so frontends have to
+    /// opt in to capturing.
+    fn request_camera_access(&mut self, _device: &CameraDevice) -> CameraAccess {
+        CameraAccess::Deny
+    }
 }
 
 /// A mouse cursor icon displayed by the Flash Player.
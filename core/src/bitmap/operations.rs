@@ -1759,6 +1759,44 @@ pub fn set_pixels_from_byte_array<'gc>(
     Ok(())
 }
 
+/// Copies unpremultiplied RGBA pixels of the given size into the top left corner
+/// of a bitmap, clipping whatever doesn't fit.
+pub fn set_pixels_from_rgba<'gc>(
+    mc: &Mutation<'gc>,
+    renderer: &mut dyn RenderBackend,
+    target: BitmapDataWrapper<'gc>,
+    width: u32,
+    height: u32,
+    rgba: &[u8],
+) {
+    let mut region = PixelRegion::for_whole_size(width, height);
+    region.clamp(target.width(), target.height());
+    if region.width() == 0 || region.height() == 0 {
+        return;
+    }
+    let transparency = target.transparency();
+
+    let target = if region.width() == target.width() && region.height() == target.height() {
+        target.overwrite_cpu_pixels_from_gpu(mc).0
+    } else {
+        target.sync(renderer)
+    };
+    let mut write = target.write(mc);
+
+    for y in region.y_min..region.y_max {
+        for x in region.x_min..region.x_max {
+            let i = ((y * width + x) * 4) as usize;
+            let [r, g, b, a] = [rgba[i], rgba[i + 1], rgba[i + 2], rgba[i + 3]];
+            write.set_pixel32_raw(
+                x,
+                y,
+                Color::argb(a, r, g, b).to_premultiplied_alpha(transparency),
+            );
+        }
+    }
+    write.set_cpu_dirty(mc, region);
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn pixel_dissolve<'gc>(
     mc: &Mutation<'gc>,
@@ -0,0 +1,2178 @@
+//! Management of active cameras
+
+use crate::avm2::object::{CameraObject, CameraObjectWeak, EventObject};
+use crate::avm2::{Activation, Avm2};
+use crate::backend::camera::{
+    CameraAccess, CameraDevice, CameraEvent, CameraFormat, CameraFrame, CameraHandle, CameraMode,
+    CameraPermission,
+};
+use crate::context::UpdateContext;
+use crate::string::AvmString;
+use gc_arena::{Collect, Mutation};
+use ruffle_macros::istr;
+use ruffle_render::bitmap::{Bitmap, BitmapFormat, BitmapInfo, PixelRegion};
+use std::cell::Ref;
+use std::collections::HashMap;
+use std::time::Duration;
+use web_time::Instant;
+
+#[derive(Clone, Collect)]
+#[collect(no_drop)]
+struct ActiveCamera<'gc> {
+    /// The backend id of the device this camera captures from.
+    device_id: u32,
+
+    /// Cameras are only held weakly, so that one that the movie doesn't reference anymore
+    /// stops capturing once it's collected.
+    object: CameraObjectWeak<'gc>,
+
+    /// The backend handle of `object`, which is still needed to close the device after
+    /// `object` was collected.
+    #[collect(require_static)]
+    handle: Option<CameraHandle>,
+}
+
+/// The `code` of a `StatusEvent` dispatched on a `Camera`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CameraStatus {
+    /// The user denied access to the camera.
+    Muted,
+
+    /// The user allowed access to the camera.
+    Unmuted,
+
+    /// The device couldn't be opened.
+    Error,
+}
+
+impl CameraStatus {
+    pub fn code(self) -> &'static str {
+        match self {
+            CameraStatus::Muted => "Camera.Muted",
+            CameraStatus::Unmuted => "Camera.Unmuted",
+            CameraStatus::Error => "Camera.Error",
+        }
+    }
+
+    /// The `level` of the event, which is `"error"` for failures and `"status"` otherwise.
+    pub fn level(self) -> &'static str {
+        match self {
+            CameraStatus::Muted | CameraStatus::Unmuted => "status",
+            CameraStatus::Error => "error",
+        }
+    }
+}
+
+/// How long the list of devices is reused before the backend is asked again.
+pub const DEVICES_TTL: Duration = Duration::from_millis(500);
+
+/// Manages the collection of cameras opened by `Camera.getCamera`.
+#[derive(Collect)]
+#[collect(no_drop)]
+pub struct Cameras<'gc> {
+    cameras: Vec<ActiveCamera<'gc>>,
+
+    /// The devices listed by the backend, and when they were listed.
+    #[collect(require_static)]
+    devices: Option<(Vec<CameraDevice>, Instant)>,
+
+    /// Cameras whose device failed to open, which are yet to be told about it.
+    failed: Vec<CameraObject<'gc>>,
+
+    /// The answers of `UiBackend::request_camera_access` that are kept for the rest of
+    /// the session, by device id.
+    #[collect(require_static)]
+    remembered_access: HashMap<u32, bool>,
+}
+
+impl<'gc> Cameras<'gc> {
+    pub fn empty() -> Self {
+        Self {
+            cameras: Vec::new(),
+            devices: None,
+            failed: Vec::new(),
+            remembered_access: HashMap::new(),
+        }
+    }
+
+    /// Lists the devices of the camera backend.
+    ///
+    /// Listing devices can be slow, and movies tend to read `Camera.names`,
+    /// `Camera.isSupported` and call `Camera.getCamera` in quick succession, so the
+    /// list is reused for a short while.
+    pub fn list_devices(context: &mut UpdateContext<'gc>) -> Vec<CameraDevice> {
+        let now = Instant::now();
+        if let Some((devices, listed)) = &context.cameras.devices {
+            if now.duration_since(*listed) < DEVICES_TTL {
+                return devices.clone();
+            }
+        }
+        let mut devices = context.camera.list_devices();
+        disambiguate_names(&mut devices);
+        context.cameras.devices = Some((devices.clone(), now));
+        devices
+    }
+
+    /// Forgets the listed devices, so that the next `list_devices` asks the backend again.
+    ///
+    /// This should be called whenever devices may have been plugged in or out.
+    pub fn invalidate_devices(&mut self) {
+        self.devices = None;
+    }
+
+    /// Returns the camera that is already capturing from the given device, if any.
+    ///
+    /// Flash Player hands out the same `Camera` instance for repeated calls to
+    /// `Camera.getCamera` with the same device.
+    pub fn get(&self, mc: &Mutation<'gc>, device_id: u32) -> Option<CameraObject<'gc>> {
+        self.cameras
+            .iter()
+            .filter(|camera| camera.device_id == device_id)
+            .find_map(|camera| camera.object.upgrade(mc))
+    }
+
+    /// Starts tracking a camera that has been opened on the given device.
+    pub fn insert(&mut self, device_id: u32, object: CameraObject<'gc>) {
+        self.cameras.push(ActiveCamera {
+            device_id,
+            object: object.downgrade(),
+            handle: object.handle(),
+        });
+    }
+
+    /// The active cameras that weren't collected yet.
+    fn objects(&self, mc: &Mutation<'gc>) -> Vec<CameraObject<'gc>> {
+        self.cameras
+            .iter()
+            .filter_map(|camera| camera.object.upgrade(mc))
+            .collect()
+    }
+
+    /// Sets the backend handle of a camera, keeping track of it in case the camera is
+    /// collected.
+    fn set_handle(&mut self, object: CameraObject<'gc>, handle: Option<CameraHandle>) {
+        object.set_handle(handle);
+        if let Some(camera) = self
+            .cameras
+            .iter_mut()
+            .find(|camera| camera.object.is(object))
+        {
+            camera.handle = handle;
+        }
+    }
+
+    /// Whether the movie may capture from a device, asking the UI unless an earlier
+    /// answer was remembered.
+    ///
+    /// This must be called right before opening the device.
+    pub fn may_access(context: &mut UpdateContext<'gc>, device: &CameraDevice) -> bool {
+        if let Some(&allowed) = context.cameras.remembered_access.get(&device.id) {
+            return allowed;
+        }
+        match context.ui.request_camera_access(device) {
+            CameraAccess::Allow => true,
+            CameraAccess::Deny => false,
+            CameraAccess::AlwaysAllow => {
+                context.cameras.remembered_access.insert(device.id, true);
+                true
+            }
+            CameraAccess::AlwaysDeny => {
+                context.cameras.remembered_access.insert(device.id, false);
+                false
+            }
+        }
+    }
+
+    /// Reports a `Camera.Error` to a camera whose device failed to open.
+    ///
+    /// The status is sent on the next update, as the movie can't have added a
+    /// listener to a camera that `Camera.getCamera` is still creating.
+    pub fn report_failure(&mut self, object: CameraObject<'gc>) {
+        self.failed.push(object);
+    }
+
+    /// Closes every active camera, and forgets about them and the listed devices so
+    /// that `Camera.getCamera` opens the device again.
+    pub fn close_all(context: &mut UpdateContext<'gc>) {
+        context.cameras.invalidate_devices();
+        let mc = context.gc_context;
+        for camera in context.cameras.cameras.drain(..) {
+            if let Some(handle) = camera.handle {
+                context.camera.close(handle);
+            }
+            if let Some(object) = camera.object.upgrade(mc) {
+                object.set_handle(None);
+            }
+        }
+    }
+
+    /// Opens or closes every active camera after the camera permission changed from
+    /// `previous` to the one now in `context`.
+    pub fn resolve_permission(context: &mut UpdateContext<'gc>, previous: CameraPermission) {
+        let permission = context.camera_permission;
+        if permission == previous {
+            return;
+        }
+
+        for object in context.cameras.objects(context.gc_context) {
+            match permission {
+                CameraPermission::Granted => {
+                    if object.handle().is_some() {
+                        continue;
+                    }
+                    let device = CameraDevice {
+                        id: object.device_id(),
+                        name: object.name().clone(),
+                    };
+                    // A device that the UI denies access to stays muted.
+                    if Self::may_access(context, &device) {
+                        match context.camera.open(device.id) {
+                            Ok(handle) => {
+                                context.cameras.set_handle(object, Some(handle));
+                                object.set_muted(false);
+                                let format = context.camera.format(handle).unwrap_or_default();
+                                Self::set_format(object, format);
+                            }
+                            Err(e) => {
+                                tracing::warn!("Failed to open camera {:?}: {e}", object.name());
+                            }
+                        }
+                    }
+                }
+                CameraPermission::Denied | CameraPermission::Prompt => Self::mute(context, object),
+            }
+
+            // Asking the user again takes back access, but doesn't resolve anything yet.
+            if permission != CameraPermission::Prompt {
+                let status = if object.muted() {
+                    CameraStatus::Muted
+                } else {
+                    CameraStatus::Unmuted
+                };
+                Self::send_status(context, object, status);
+            }
+        }
+    }
+
+    /// Stops capturing from a camera, while keeping it around for when it's unmuted.
+    fn mute(context: &mut UpdateContext<'gc>, camera: CameraObject<'gc>) {
+        if let Some(handle) = camera.handle() {
+            context.camera.close(handle);
+        }
+        context.cameras.set_handle(camera, None);
+        camera.set_muted(true);
+        camera.set_frame(None);
+    }
+
+    /// Dispatches a `StatusEvent` on a camera, with the code and level of `status`.
+    fn send_status(
+        context: &mut UpdateContext<'gc>,
+        camera: CameraObject<'gc>,
+        status: CameraStatus,
+    ) {
+        let mut activation = Activation::from_nothing(context);
+
+        let status_event = activation.avm2().classes().statusevent;
+        let code = AvmString::new_utf8(activation.gc(), status.code());
+        let level = AvmString::new_utf8(activation.gc(), status.level());
+        let event = EventObject::from_class_and_args(
+            &mut activation,
+            status_event,
+            &[
+                istr!("status").into(),
+                false.into(),
+                false.into(),
+                code.into(),
+                level.into(),
+            ],
+        );
+        Avm2::dispatch_event(activation.context, event, camera.into());
+    }
+
+    /// Forgets a camera whose device was unplugged. Flash Player has no event for this,
+    /// so the camera is muted and reports `Camera.Muted`, like when access is revoked.
+    ///
+    /// The camera stops capturing for good, but `Camera.getCamera` opens the device
+    /// again once it's plugged back in.
+    fn disconnect(context: &mut UpdateContext<'gc>, camera: CameraObject<'gc>) {
+        if let Some(handle) = camera.handle() {
+            context.camera.close(handle);
+        }
+        camera.set_handle(None);
+        camera.set_frame(None);
+        context
+            .cameras
+            .cameras
+            .retain(|active| !active.object.is(camera));
+        context.cameras.invalidate_devices();
+        camera.set_muted(true);
+        Self::send_status(context, camera, CameraStatus::Muted);
+    }
+
+    /// Applies pending format changes and uploads the latest frame of every active camera,
+    /// dispatching `activity` events when motion starts or stops.
+    pub fn update_cameras(context: &mut UpdateContext<'gc>) {
+        for camera in std::mem::take(&mut context.cameras.failed) {
+            Self::send_status(context, camera, CameraStatus::Error);
+        }
+
+        // Cameras that the movie dropped are closed once they're collected.
+        context.cameras.cameras.retain(|camera| {
+            if !camera.object.is_dropped() {
+                return true;
+            }
+            if let Some(handle) = camera.handle {
+                context.camera.close(handle);
+            }
+            false
+        });
+
+        let cameras = context.cameras.objects(context.gc_context);
+
+        'cameras: for camera in cameras {
+            let Some(handle) = camera.handle() else {
+                continue;
+            };
+            // Format changes are left pending as well, so the frozen frame stays visible.
+            if camera.frozen() {
+                continue;
+            }
+
+            // Format changes must be applied before the next frame is uploaded,
+            // otherwise it would be written into a texture of the wrong size.
+            while let Some(event) = context.camera.poll_event(handle) {
+                match event {
+                    CameraEvent::FormatChanged(format) => Self::set_format(camera, format),
+                    CameraEvent::Disconnected => {
+                        Self::disconnect(context, camera);
+                        continue 'cameras;
+                    }
+                }
+            }
+
+            let frame = Self::next_frame(context, camera, handle);
+            let activity_level = frame
+                .as_ref()
+                .map(|frame| camera.measure_activity(&frame.data));
+            if let Some(frame) = frame {
+                Self::upload_frame(context, camera, frame);
+            }
+
+            // Activity is checked even without a new frame, so that it times out once
+            // the device stops delivering any.
+            if let Some(activating) = camera.update_activity(activity_level, Instant::now()) {
+                Self::send_activity(context, camera, activating);
+            }
+        }
+    }
+
+    /// Takes the next frame of a camera, converted to RGBA if the device delivered it
+    /// in another pixel format.
+    fn next_frame(
+        context: &mut UpdateContext<'gc>,
+        camera: CameraObject<'gc>,
+        handle: CameraHandle,
+    ) -> Option<CameraFrame> {
+        let frame = context.camera.next_frame(handle)?;
+        camera.set_pixel_format(frame.pixel_format);
+        Some(frame.into_rgba())
+    }
+
+    /// Displays a frame, which must have been converted to RGBA already.
+    fn upload_frame(
+        context: &mut UpdateContext<'gc>,
+        camera: CameraObject<'gc>,
+        frame: CameraFrame,
+    ) {
+        // Backends should announce every format change, but don't trust them blindly.
+        if frame.format != camera.format() {
+            tracing::warn!(
+                "Camera frame format {:?} doesn't match the announced format {:?}",
+                frame.format,
+                camera.format()
+            );
+            Self::set_format(camera, frame.format);
+        }
+
+        let CameraFormat { width, height, .. } = frame.format;
+        let mut rgba = frame.data;
+        if camera.mirrored() {
+            mirror_horizontally(&mut rgba, width);
+        }
+        // The renderer takes ownership of the bitmap, so it's given a copy of the frame.
+        let bitmap = Bitmap::new(width, height, BitmapFormat::Rgba, rgba.clone());
+
+        if let Some(info) = camera.frame() {
+            if let Err(e) = context.renderer.update_texture(
+                &info.handle,
+                bitmap,
+                PixelRegion::for_whole_size(width, height),
+            ) {
+                tracing::error!("Failed to update camera frame: {e:?}");
+            }
+        } else {
+            match context.renderer.register_bitmap(bitmap) {
+                Ok(handle) => camera.set_frame(Some(BitmapInfo {
+                    handle,
+                    width: width as u16,
+                    height: height as u16,
+                })),
+                Err(e) => tracing::error!("Failed to register camera frame: {e:?}"),
+            }
+        }
+        camera.set_pixels(rgba);
+    }
+
+    /// Returns the width, height and RGBA pixels of a camera's current image, for
+    /// `Camera.drawToBitmapData`.
+    ///
+    /// A camera that didn't receive a frame yet is asked for one right away, so this
+    /// works without a `Video` showing the camera. A camera without an open device,
+    /// like one that failed to open, opens it after the same access checks as
+    /// `Camera.getCamera` and keeps it open. As backends capture on threads of their
+    /// own, its first frame may only be drawn by a later call.
+    pub fn grab_frame(
+        context: &mut UpdateContext<'gc>,
+        camera: CameraObject<'gc>,
+    ) -> Option<(u32, u32, Ref<'gc, [u8]>)> {
+        let handle = match camera.handle() {
+            Some(handle) => handle,
+            None => Self::reopen(context, camera)?,
+        };
+        if camera.pixels().is_none() && !camera.frozen() {
+            if let Some(frame) = Self::next_frame(context, camera, handle) {
+                Self::upload_frame(context, camera, frame);
+            }
+        }
+        let CameraFormat { width, height, .. } = camera.format();
+        let pixels = Ref::filter_map(camera.pixels(), |pixels| pixels.as_deref()).ok()?;
+        Some((width, height, pixels))
+    }
+
+    /// Opens the device of a camera that isn't capturing, and starts tracking it.
+    fn reopen(context: &mut UpdateContext<'gc>, camera: CameraObject<'gc>) -> Option<CameraHandle> {
+        // A muted camera may not capture, even just a single frame.
+        if context.camera_permission != CameraPermission::Granted
+            || camera.index().is_none()
+            || camera.muted()
+        {
+            return None;
+        }
+        let device = CameraDevice {
+            id: camera.device_id(),
+            name: camera.name().clone(),
+        };
+        if !Self::may_access(context, &device) {
+            return None;
+        }
+        let handle = match context.camera.open(device.id) {
+            Ok(handle) => handle,
+            Err(e) => {
+                tracing::warn!("Failed to open camera {:?}: {e}", device.name);
+                return None;
+            }
+        };
+        camera.set_handle(Some(handle));
+        camera.set_format(context.camera.format(handle).unwrap_or_default());
+        context.cameras.insert(device.id, camera);
+        Some(handle)
+    }
+
+    /// Dispatches an `activity` event, telling the movie whether the camera started or
+    /// stopped detecting motion.
+    pub fn send_activity(
+        context: &mut UpdateContext<'gc>,
+        camera: CameraObject<'gc>,
+        activating: bool,
+    ) {
+        let mut activation = Activation::from_nothing(context);
+
+        let activity_event = activation.avm2().classes().activityevent;
+        let activity = AvmString::new_utf8(activation.gc(), "activity");
+        let event = EventObject::from_class_and_args(
+            &mut activation,
+            activity_event,
+            &[
+                activity.into(),
+                false.into(),
+                false.into(),
+                activating.into(),
+            ],
+        );
+        Avm2::dispatch_event(activation.context, event, camera.into());
+    }
+
+    /// Switches a camera to a new frame format.
+    ///
+    /// The texture of the previous format is dropped, so it will be reallocated
+    /// with the new dimensions when the next frame arrives.
+    pub fn set_format(camera: CameraObject<'gc>, format: CameraFormat) {
+        if camera.format() != format {
+            camera.set_format(format);
+            camera.set_frame(None);
+        }
+    }
+}
+
+/// Pixels whose brightness changes by less than this between frames are considered noise.
+const ACTIVITY_NOISE_THRESHOLD: u8 = 16;
+
+/// Detects motion in the frames captured by a camera, for `Camera.activityLevel` and
+/// the `activity` events.
+#[derive(Default)]
+pub struct ActivityDetector {
+    /// The brightness of every pixel of the previous frame.
+    previous: Vec<u8>,
+
+    /// When motion was detected last, or `None` while the camera is inactive.
+    last_motion: Option<Instant>,
+}
+
+impl ActivityDetector {
+    /// Compares a frame to the previous one, returning the percentage of pixels whose
+    /// brightness changed.
+    pub fn measure(&mut self, rgba: &[u8]) -> f64 {
+        let brightness: Vec<u8> = rgba
+            .chunks_exact(4)
+            .map(|pixel| ((pixel[0] as u16 + pixel[1] as u16 + pixel[2] as u16) / 3) as u8)
+            .collect();
+
+        // Nothing to compare the first frame, or the first one in a new size, to.
+        let level = if brightness.is_empty() || brightness.len() != self.previous.len() {
+            0.0
+        } else {
+            let changed = brightness
+                .iter()
+                .zip(&self.previous)
+                .filter(|(current, previous)| {
+                    current.abs_diff(**previous) >= ACTIVITY_NOISE_THRESHOLD
+                })
+                .count();
+            changed as f64 * 100.0 / brightness.len() as f64
+        };
+        self.previous = brightness;
+        level
+    }
+
+    /// Updates whether the camera is active, returning the new state if it changed.
+    ///
+    /// The camera becomes active as soon as `activity_level` reaches `motion_level`, and
+    /// inactive again once it stayed below that for `timeout`. `activity_level` is `None`
+    /// when no frame was captured since the last update.
+    pub fn update(
+        &mut self,
+        activity_level: Option<f64>,
+        motion_level: i32,
+        timeout: Duration,
+        now: Instant,
+    ) -> Option<bool> {
+        // Like in Flash Player, a motion level of 100 never detects any motion.
+        let motion =
+            motion_level < 100 && activity_level.is_some_and(|level| level >= motion_level as f64);
+        if motion {
+            let activating = self.last_motion.is_none();
+            self.last_motion = Some(now);
+            return activating.then_some(true);
+        }
+
+        match self.last_motion {
+            Some(last_motion) if now.duration_since(last_motion) >= timeout => {
+                self.last_motion = None;
+                Some(false)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Appends a ` #2`, ` #3`... suffix to every device named like one before it, so that
+/// identical devices can be told apart in `Camera.names` and picked by name.
+fn disambiguate_names(devices: &mut [CameraDevice]) {
+    let mut seen: Vec<(String, u32)> = Vec::new();
+    for device in devices {
+        match seen.iter_mut().find(|(name, _)| *name == device.name) {
+            Some((_, count)) => {
+                *count += 1;
+                device.name = format!("{} #{count}", device.name);
+            }
+            None => seen.push((device.name.clone(), 1)),
+        }
+    }
+}
+
+/// Flips RGBA pixels horizontally, so that each row is reversed.
+fn mirror_horizontally(rgba: &mut [u8], width: u32) {
+    if width == 0 {
+        return;
+    }
+    for row in rgba.chunks_exact_mut(width as usize * 4) {
+        // Reversing the bytes also reverses the channels of every pixel, so undo that.
+        row.reverse();
+        for pixel in row.chunks_exact_mut(4) {
+            pixel.reverse();
+        }
+    }
+}
+
+/// Picks the supported mode that best matches a `Camera.setMode` request.
+///
+/// With `favor_area`, the mode whose pixel area is closest to the requested one wins,
+/// even if it captures at a lower frame rate. Without it, modes that reach the requested
+/// frame rate are preferred, even if that means capturing at a smaller size.
+///
+/// Modes of the same area are told apart by how close their width and height are to
+/// the requested ones.
+pub fn select_mode(
+    modes: &[CameraMode],
+    requested: CameraMode,
+    favor_area: bool,
+) -> Option<CameraMode> {
+    let area = |mode: &CameraMode| mode.width as u64 * mode.height as u64;
+    let area_distance = |mode: &CameraMode| area(mode).abs_diff(area(&requested));
+    let size_distance = |mode: &CameraMode| {
+        mode.width.abs_diff(requested.width) as u64 + mode.height.abs_diff(requested.height) as u64
+    };
+    let fps_distance = |mode: &CameraMode| (mode.fps - requested.fps).abs();
+    let fps_shortfall = |mode: &CameraMode| (requested.fps - mode.fps).max(0.0);
+
+    modes.iter().copied().min_by(|a, b| {
+        let by_size = || {
+            area_distance(a)
+                .cmp(&area_distance(b))
+                .then(size_distance(a).cmp(&size_distance(b)))
+        };
+        if favor_area {
+            by_size().then(fps_distance(a).total_cmp(&fps_distance(b)))
+        } else {
+            fps_shortfall(a)
+                .total_cmp(&fps_shortfall(b))
+                .then_with(by_size)
+                .then(fps_distance(a).total_cmp(&fps_distance(b)))
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::avm2::object::{ArrayObject, EventObject, Object, TObject};
+    use crate::avm2::test_utils::with_avm2;
+    use crate::avm2::{Activation, ArrayStorage, Avm2, Error, Value};
+    use crate::backend::camera::{
+        CameraAccess, CameraBackend, CameraDevice, CameraError, CameraEvent, CameraFormat,
+        CameraFrame, CameraHandle, CameraMode, CameraPermission, CameraPixelFormat,
+        NullCameraBackend,
+    };
+    use crate::backend::ui::{
+        DialogResultFuture, FileFilter, FontDefinition, FullscreenError, LanguageIdentifier,
+        MouseCursor, NullUiBackend, UiBackend,
+    };
+    use crate::camera::{
+        disambiguate_names, mirror_horizontally, select_mode, ActivityDetector, Cameras,
+    };
+    use crate::font::FontQuery;
+    use crate::player::{Player, PlayerBuilder};
+    use crate::string::AvmString;
+    use ruffle_macros::istr;
+    use slotmap::SlotMap;
+    use std::cell::Cell;
+    use std::collections::VecDeque;
+    use std::rc::Rc;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::Duration;
+    use url::Url;
+    use web_time::Instant;
+
+    /// A player using the given camera backend, whose user allowed the movie to capture
+    /// from any device.
+    fn camera_player(camera: impl 'static + CameraBackend) -> PlayerBuilder {
+        PlayerBuilder::new()
+            .with_camera(camera)
+            .with_camera_permission(CameraPermission::Granted)
+            .with_ui(CameraPrompt {
+                answer: CameraAccess::Allow,
+                asked: Rc::default(),
+                ui: NullUiBackend::new(),
+            })
+    }
+
+    /// Calls `Camera.getCamera` with the given arguments.
+    fn get_camera<'gc>(
+        activation: &mut Activation<'_, 'gc>,
+        args: &[Value<'gc>],
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        let camera_class: Value = activation.avm2().classes().camera.into();
+        let get_camera = AvmString::new_utf8(activation.gc(), "getCamera");
+        camera_class.call_public_property(get_camera, args, activation)
+    }
+
+    /// Gets the first camera, along with an array recording the events of the given
+    /// type that it receives.
+    ///
+    /// Events are recorded by using a bound `Array.push` as the listener.
+    fn camera_with_events<'gc>(
+        activation: &mut Activation<'_, 'gc>,
+        event_type: &str,
+    ) -> Result<(Value<'gc>, Value<'gc>), Error<'gc>> {
+        let camera = get_camera(activation, &[])?;
+        let received: Value = ArrayObject::from_storage(activation, ArrayStorage::new(0)).into();
+        let push = AvmString::new_utf8(activation.gc(), "push");
+        let listener = received.get_public_property(push, activation)?;
+        let add_event_listener = AvmString::new_utf8(activation.gc(), "addEventListener");
+        let event_type = AvmString::new_utf8(activation.gc(), event_type);
+        camera.call_public_property(
+            add_event_listener,
+            &[event_type.into(), listener],
+            activation,
+        )?;
+        Ok((camera, received))
+    }
+
+    /// The `code` and `level` of a recorded status event.
+    fn status_of<'gc>(
+        activation: &mut Activation<'_, 'gc>,
+        event: Value<'gc>,
+    ) -> Result<(String, String), Error<'gc>> {
+        let code = AvmString::new_utf8(activation.gc(), "code");
+        let level = AvmString::new_utf8(activation.gc(), "level");
+        let code = event.get_public_property(code, activation)?;
+        let level = event.get_public_property(level, activation)?;
+        Ok((
+            code.coerce_to_string(activation)?.to_string(),
+            level.coerce_to_string(activation)?.to_string(),
+        ))
+    }
+
+    /// A thread capturing for an open `ScriptedCamera`, and the flag that stops it.
+    type CaptureThread = (Arc<AtomicBool>, thread::JoinHandle<()>);
+
+    /// A camera that plays back a fixed list of frames, announcing a format change
+    /// whenever the size of the next frame differs from the current one.
+    struct ScriptedCamera {
+        device: CameraDevice,
+
+        /// The open handles, with the capture thread of each one in threaded mode.
+        handles: SlotMap<CameraHandle, Option<CaptureThread>>,
+        format: CameraFormat,
+        frames: VecDeque<CameraFrame>,
+        modes: Vec<CameraMode>,
+        frozen: bool,
+        current_fps: f64,
+
+        /// Whether the device is unplugged once all frames were played back.
+        unplugged: bool,
+
+        /// Whether the device fails to open, as it's used by another application.
+        busy: Rc<Cell<bool>>,
+
+        /// Whether every open handle captures on a thread of its own, like the desktop
+        /// backends do. Like those, it relies on being closed: the thread keeps running
+        /// otherwise.
+        threaded: bool,
+
+        /// The number of capture threads that didn't exit yet.
+        running: Arc<AtomicUsize>,
+
+        /// The number of frames handed out so far.
+        delivered: Rc<Cell<usize>>,
+
+        /// The number of times the devices were listed so far.
+        enumerations: Rc<Cell<usize>>,
+
+        /// The pixel format that was last asked for.
+        preferred_pixel_format: Rc<Cell<CameraPixelFormat>>,
+    }
+
+    impl ScriptedCamera {
+        fn new(sizes: &[(u32, u32)]) -> Self {
+            let formats: Vec<_> = sizes
+                .iter()
+                .map(|&(width, height)| CameraFormat::new(width, height))
+                .collect();
+            Self::with_formats(&formats)
+        }
+
+        fn with_formats(formats: &[CameraFormat]) -> Self {
+            let frames = formats
+                .iter()
+                .map(|&format| {
+                    CameraFrame::rgba(
+                        format,
+                        vec![0xFF; (format.width * format.height * 4) as usize],
+                    )
+                })
+                .collect::<VecDeque<_>>();
+            Self {
+                device: CameraDevice {
+                    id: 0,
+                    name: "Scripted Camera".to_string(),
+                },
+                handles: SlotMap::with_key(),
+                format: frames.front().map(|frame| frame.format).unwrap_or_default(),
+                frames,
+                modes: Vec::new(),
+                frozen: false,
+                current_fps: 0.0,
+                unplugged: false,
+                busy: Rc::new(Cell::new(false)),
+                threaded: false,
+                running: Arc::new(AtomicUsize::new(0)),
+                delivered: Rc::new(Cell::new(0)),
+                enumerations: Rc::new(Cell::new(0)),
+                preferred_pixel_format: Rc::new(Cell::new(CameraPixelFormat::Rgba)),
+            }
+        }
+
+        fn with_modes(mut self, modes: &[(u32, u32, f64)]) -> Self {
+            self.modes = modes
+                .iter()
+                .map(|&(width, height, fps)| CameraMode { width, height, fps })
+                .collect();
+            self
+        }
+
+        fn with_device(mut self, device: CameraDevice) -> Self {
+            self.device = device;
+            self
+        }
+
+        fn with_current_fps(mut self, current_fps: f64) -> Self {
+            self.current_fps = current_fps;
+            self
+        }
+
+        fn unplugged_after_playback(mut self) -> Self {
+            self.unplugged = true;
+            self
+        }
+
+        fn busy(mut self) -> Self {
+            self.busy.set(true);
+            self
+        }
+
+        fn threaded(mut self) -> Self {
+            self.threaded = true;
+            self
+        }
+    }
+
+    impl CameraBackend for ScriptedCamera {
+        fn list_devices(&mut self) -> Vec<CameraDevice> {
+            self.enumerations.set(self.enumerations.get() + 1);
+            vec![self.device.clone()]
+        }
+
+        fn open(&mut self, _id: u32) -> Result<CameraHandle, CameraError> {
+            if self.busy.get() {
+                return Err(CameraError::Busy);
+            }
+            if !self.threaded {
+                return Ok(self.handles.insert(None));
+            }
+
+            let stop = Arc::new(AtomicBool::new(false));
+            self.running.fetch_add(1, Ordering::SeqCst);
+            let thread = thread::spawn({
+                let stop = stop.clone();
+                let running = self.running.clone();
+                move || {
+                    while !stop.load(Ordering::SeqCst) {
+                        thread::sleep(Duration::from_millis(1));
+                    }
+                    running.fetch_sub(1, Ordering::SeqCst);
+                }
+            });
+            Ok(self.handles.insert(Some((stop, thread))))
+        }
+
+        fn close(&mut self, handle: CameraHandle) {
+            if let Some(Some((stop, thread))) = self.handles.remove(handle) {
+                stop.store(true, Ordering::SeqCst);
+                thread.join().expect("Capture thread panicked");
+            }
+        }
+
+        fn format(&self, _handle: CameraHandle) -> Option<CameraFormat> {
+            Some(self.format)
+        }
+
+        fn supported_modes(&self, _handle: CameraHandle) -> Vec<CameraMode> {
+            self.modes.clone()
+        }
+
+        fn set_mode(&mut self, _handle: CameraHandle, mode: CameraMode) -> Result<(), CameraError> {
+            if !self.modes.contains(&mode) {
+                return Err(CameraError::Other(format!("Unsupported mode {mode:?}")));
+            }
+            self.format = CameraFormat::new(mode.width, mode.height);
+            self.frames.clear();
+            Ok(())
+        }
+
+        fn set_preferred_pixel_format(
+            &mut self,
+            _handle: CameraHandle,
+            pixel_format: CameraPixelFormat,
+        ) {
+            self.preferred_pixel_format.set(pixel_format);
+        }
+
+        fn set_frozen(&mut self, _handle: CameraHandle, frozen: bool) {
+            self.frozen = frozen;
+        }
+
+        fn current_fps(&self, _handle: CameraHandle) -> f64 {
+            self.current_fps
+        }
+
+        fn poll_event(&mut self, handle: CameraHandle) -> Option<CameraEvent> {
+            if self.unplugged && self.frames.is_empty() && self.handles.contains_key(handle) {
+                return Some(CameraEvent::Disconnected);
+            }
+            let next = self.frames.front()?.format;
+            if next != self.format {
+                self.format = next;
+                return Some(CameraEvent::FormatChanged(next));
+            }
+            None
+        }
+
+        fn next_frame(&mut self, _handle: CameraHandle) -> Option<CameraFrame> {
+            if self.frozen || self.frames.front()?.format != self.format {
+                return None;
+            }
+            self.delivered.set(self.delivered.get() + 1);
+            self.frames.pop_front()
+        }
+    }
+
+    #[test]
+    fn format_change_updates_size_and_texture() {
+        let camera = ScriptedCamera::new(&[(320, 240), (640, 480)]);
+        let builder = camera_player(camera);
+        with_avm2(builder, |activation| {
+            let camera = get_camera(activation, &[])?;
+            let object = camera.as_object().unwrap().as_camera().unwrap();
+
+            Cameras::update_cameras(activation.context);
+            assert_eq!(
+                camera.get_public_property(istr!("width"), activation)?,
+                320.into()
+            );
+            assert_eq!(
+                camera.get_public_property(istr!("height"), activation)?,
+                240.into()
+            );
+            let frame = object.frame().expect("First frame should be uploaded");
+            assert_eq!((frame.width, frame.height), (320, 240));
+
+            Cameras::update_cameras(activation.context);
+            assert_eq!(
+                camera.get_public_property(istr!("width"), activation)?,
+                640.into()
+            );
+            assert_eq!(
+                camera.get_public_property(istr!("height"), activation)?,
+                480.into()
+            );
+            let frame = object.frame().expect("Texture should be reallocated");
+            assert_eq!((frame.width, frame.height), (640, 480));
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn event_target_has_device_name() {
+        let camera = ScriptedCamera::new(&[(320, 240)]);
+        let builder = camera_player(camera);
+        with_avm2(builder, |activation| {
+            let (camera, received) = camera_with_events(activation, "status")?;
+
+            let status_event = activation.avm2().classes().statusevent;
+            let event = EventObject::from_class_and_args(
+                activation,
+                status_event,
+                &[
+                    istr!("status").into(),
+                    false.into(),
+                    false.into(),
+                    Value::Null,
+                    istr!("status").into(),
+                ],
+            );
+            Avm2::dispatch_event(activation.context, event, camera.as_object().unwrap());
+
+            let event = received
+                .as_object()
+                .unwrap()
+                .as_array_storage()
+                .unwrap()
+                .get(0)
+                .expect("Listener should have received the event");
+            let target = event.get_public_property(istr!("target"), activation)?;
+            assert_eq!(target, camera);
+            let name = target.get_public_property(istr!("name"), activation)?;
+            assert_eq!(
+                name,
+                AvmString::new_utf8(activation.gc(), "Scripted Camera").into()
+            );
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn denied_permission_mutes_camera() {
+        let camera = ScriptedCamera::new(&[(320, 240)]);
+        let builder = camera_player(camera).with_camera_permission(CameraPermission::Denied);
+        with_avm2(builder, |activation| {
+            let camera = get_camera(activation, &[])?;
+            let object = camera.as_object().unwrap().as_camera().unwrap();
+
+            let muted = AvmString::new_utf8(activation.gc(), "muted");
+            assert_eq!(camera.get_public_property(muted, activation)?, true.into());
+
+            Cameras::update_cameras(activation.context);
+            assert!(object.frame().is_none());
+
+            Ok(())
+        });
+    }
+
+    /// A UI that answers every camera access request the same way.
+    struct CameraPrompt {
+        answer: CameraAccess,
+        asked: Rc<Cell<usize>>,
+        ui: NullUiBackend,
+    }
+
+    impl UiBackend for CameraPrompt {
+        fn mouse_visible(&self) -> bool {
+            self.ui.mouse_visible()
+        }
+
+        fn set_mouse_visible(&mut self, visible: bool) {
+            self.ui.set_mouse_visible(visible)
+        }
+
+        fn set_mouse_cursor(&mut self, cursor: MouseCursor) {
+            self.ui.set_mouse_cursor(cursor)
+        }
+
+        fn clipboard_content(&mut self) -> String {
+            self.ui.clipboard_content()
+        }
+
+        fn set_clipboard_content(&mut self, content: String) {
+            self.ui.set_clipboard_content(content)
+        }
+
+        fn set_fullscreen(&mut self, is_full: bool) -> Result<(), FullscreenError> {
+            self.ui.set_fullscreen(is_full)
+        }
+
+        fn display_root_movie_download_failed_message(&self, invalid_swf: bool) {
+            self.ui
+                .display_root_movie_download_failed_message(invalid_swf)
+        }
+
+        fn message(&self, message: &str) {
+            self.ui.message(message)
+        }
+
+        fn open_virtual_keyboard(&self) {}
+
+        fn close_virtual_keyboard(&self) {}
+
+        fn language(&self) -> LanguageIdentifier {
+            self.ui.language()
+        }
+
+        fn display_unsupported_video(&self, url: Url) {
+            self.ui.display_unsupported_video(url)
+        }
+
+        fn load_device_font(&self, query: &FontQuery, register: &mut dyn FnMut(FontDefinition)) {
+            self.ui.load_device_font(query, register)
+        }
+
+        fn sort_device_fonts(
+            &self,
+            query: &FontQuery,
+            register: &mut dyn FnMut(FontDefinition),
+        ) -> Vec<FontQuery> {
+            self.ui.sort_device_fonts(query, register)
+        }
+
+        fn display_file_open_dialog(
+            &mut self,
+            filters: Vec<FileFilter>,
+        ) -> Option<DialogResultFuture> {
+            self.ui.display_file_open_dialog(filters)
+        }
+
+        fn display_file_save_dialog(
+            &mut self,
+            file_name: String,
+            title: String,
+        ) -> Option<DialogResultFuture> {
+            self.ui.display_file_save_dialog(file_name, title)
+        }
+
+        fn close_file_dialog(&mut self) {
+            self.ui.close_file_dialog()
+        }
+
+        fn request_camera_access(&mut self, _device: &CameraDevice) -> CameraAccess {
+            self.asked.set(self.asked.get() + 1);
+            self.answer
+        }
+    }
+
+    #[test]
+    fn remembered_denial_mutes_camera() {
+        let asked = Rc::new(Cell::new(0));
+        let builder = camera_player(ScriptedCamera::new(&[(320, 240)])).with_ui(CameraPrompt {
+            answer: CameraAccess::AlwaysDeny,
+            asked: asked.clone(),
+            ui: NullUiBackend::new(),
+        });
+        with_avm2(builder, |activation| {
+            let camera = get_camera(activation, &[])?;
+            let object = camera.as_object().unwrap().as_camera().unwrap();
+            assert!(object.muted());
+            assert!(object.handle().is_none());
+
+            Cameras::update_cameras(activation.context);
+            assert!(object.frame().is_none());
+
+            // The answer is remembered, even for a new camera on the same device.
+            Cameras::close_all(activation.context);
+            let camera = get_camera(activation, &[])?;
+            assert!(camera.as_object().unwrap().as_camera().unwrap().muted());
+            assert_eq!(asked.get(), 1);
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn camera_is_muted_until_permission_is_granted() {
+        let builder = PlayerBuilder::new().with_camera(ScriptedCamera::new(&[(320, 240)]));
+        with_avm2(builder, |activation| {
+            assert_eq!(
+                activation.context.camera_permission,
+                CameraPermission::Prompt
+            );
+
+            let camera = get_camera(activation, &[])?;
+            let muted = AvmString::new_utf8(activation.gc(), "muted");
+            assert_eq!(camera.get_public_property(muted, activation)?, true.into());
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn devices_are_denied_unless_the_ui_allows_them() {
+        let builder = PlayerBuilder::new()
+            .with_camera(ScriptedCamera::new(&[(320, 240)]))
+            .with_camera_permission(CameraPermission::Granted);
+        with_avm2(builder, |activation| {
+            let camera = get_camera(activation, &[])?;
+            let object = camera.as_object().unwrap().as_camera().unwrap();
+            assert!(object.muted());
+            assert!(object.handle().is_none());
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn granted_permission_activates_camera() {
+        let camera = ScriptedCamera::new(&[(320, 240)]);
+        let builder = camera_player(camera).with_camera_permission(CameraPermission::Granted);
+        with_avm2(builder, |activation| {
+            let camera = get_camera(activation, &[])?;
+            let object = camera.as_object().unwrap().as_camera().unwrap();
+
+            let muted = AvmString::new_utf8(activation.gc(), "muted");
+            assert_eq!(camera.get_public_property(muted, activation)?, false.into());
+
+            Cameras::update_cameras(activation.context);
+            assert!(object.frame().is_some());
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn non_square_pixels_are_corrected() {
+        let format = CameraFormat {
+            width: 720,
+            height: 576,
+            pixel_aspect_ratio: (16, 15),
+        };
+        let camera = ScriptedCamera::with_formats(&[format]);
+        let builder = camera_player(camera);
+        with_avm2(builder, |activation| {
+            let camera = get_camera(activation, &[])?;
+            let object = camera.as_object().unwrap().as_camera().unwrap();
+
+            Cameras::update_cameras(activation.context);
+            assert_eq!(object.format().display_size(), (768, 576));
+
+            // The texture still holds the frame as it was captured.
+            let frame = object.frame().expect("Frame should be uploaded");
+            assert_eq!((frame.width, frame.height), (720, 576));
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn square_pixels_are_untouched() {
+        assert_eq!(CameraFormat::new(640, 480).display_size(), (640, 480));
+        let format = CameraFormat {
+            pixel_aspect_ratio: (0, 0),
+            ..CameraFormat::new(640, 480)
+        };
+        assert_eq!(format.display_size(), (640, 480));
+    }
+
+    #[test]
+    fn invalid_utf8_name_is_selectable_by_name() {
+        let camera = ScriptedCamera::new(&[(320, 240)])
+            .with_device(CameraDevice::from_raw_name(0, b"Web\xc3cam\0\0\0"));
+        let builder = camera_player(camera);
+        with_avm2(builder, |activation| {
+            let camera_class: Value = activation.avm2().classes().camera.into();
+            let names = AvmString::new_utf8(activation.gc(), "names");
+            let name = camera_class
+                .get_public_property(names, activation)?
+                .as_object()
+                .unwrap()
+                .as_array_storage()
+                .unwrap()
+                .get(0)
+                .expect("Device should be listed");
+            assert_eq!(
+                name,
+                AvmString::new_utf8(activation.gc(), "Web\u{FFFD}cam").into()
+            );
+
+            let camera = get_camera(activation, &[name])?;
+            assert!(camera.as_object().and_then(|o| o.as_camera()).is_some());
+            assert_eq!(camera.get_public_property(istr!("name"), activation)?, name);
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn set_cursor_is_remembered() {
+        let builder = camera_player(ScriptedCamera::new(&[(320, 240)]));
+        with_avm2(builder, |activation| {
+            let camera = activation
+                .avm2()
+                .classes()
+                .camera
+                .construct(activation, &[])?;
+            let set_cursor = AvmString::new_utf8(activation.gc(), "setCursor");
+            assert!(!camera.as_camera().unwrap().cursor());
+
+            camera.call_public_property(set_cursor, &[true.into()], activation)?;
+            assert!(camera.as_camera().unwrap().cursor());
+
+            camera.call_public_property(set_cursor, &[false.into()], activation)?;
+            assert!(!camera.as_camera().unwrap().cursor());
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn frame_is_drawn_without_video() {
+        let builder = camera_player(ScriptedCamera::new(&[(2, 2)]));
+        with_avm2(builder, |activation| {
+            let camera = get_camera(activation, &[])?;
+
+            let bitmap_data: Value = activation
+                .avm2()
+                .classes()
+                .bitmapdata
+                .construct(activation, &[4.into(), 4.into(), true.into(), 0.into()])?;
+            let draw_to_bitmap_data = AvmString::new_utf8(activation.gc(), "drawToBitmapData");
+            camera.call_public_property(draw_to_bitmap_data, &[bitmap_data], activation)?;
+
+            // Only the top left corner is covered by the 2x2 frame.
+            let get_pixel32 = AvmString::new_utf8(activation.gc(), "getPixel32");
+            for (x, y, expected) in [(1, 1, 0xFFFFFFFF), (2, 2, 0)] {
+                let pixel = bitmap_data.call_public_property(
+                    get_pixel32,
+                    &[x.into(), y.into()],
+                    activation,
+                )?;
+                assert_eq!(pixel.coerce_to_u32(activation)?, expected);
+            }
+
+            Ok(())
+        });
+    }
+
+    const MODES: &[(u32, u32, f64)] = &[(640, 480, 15.0), (320, 240, 30.0), (160, 120, 30.0)];
+
+    #[test]
+    fn favor_area_keeps_resolution() {
+        let modes: Vec<_> = ScriptedCamera::new(&[]).with_modes(MODES).modes;
+        let requested = CameraMode {
+            width: 640,
+            height: 480,
+            fps: 30.0,
+        };
+        assert_eq!(
+            select_mode(&modes, requested, true),
+            Some(CameraMode {
+                width: 640,
+                height: 480,
+                fps: 15.0
+            })
+        );
+        assert_eq!(select_mode(&[], requested, true), None);
+    }
+
+    #[test]
+    fn modes_are_scored_by_area() {
+        let modes: Vec<_> = ScriptedCamera::new(&[])
+            .with_modes(&[(640, 250, 15.0), (320, 320, 30.0), (480, 320, 30.0)])
+            .modes;
+        let requested = CameraMode {
+            width: 400,
+            height: 400,
+            fps: 30.0,
+        };
+
+        // 640x250 has the requested area, despite being the furthest off in shape.
+        assert_eq!(
+            select_mode(&modes, requested, true),
+            Some(CameraMode {
+                width: 640,
+                height: 250,
+                fps: 15.0
+            })
+        );
+
+        // Among the modes that reach 30fps, 480x320 is closest in area.
+        assert_eq!(
+            select_mode(&modes, requested, false),
+            Some(CameraMode {
+                width: 480,
+                height: 320,
+                fps: 30.0
+            })
+        );
+    }
+
+    #[test]
+    fn favor_frame_rate_reduces_resolution() {
+        let camera = ScriptedCamera::new(&[(640, 480)]).with_modes(MODES);
+        let builder = camera_player(camera);
+        with_avm2(builder, |activation| {
+            let camera = get_camera(activation, &[])?;
+
+            let set_mode = AvmString::new_utf8(activation.gc(), "setMode");
+            camera.call_public_property(
+                set_mode,
+                &[640.into(), 480.into(), 30.into(), false.into()],
+                activation,
+            )?;
+
+            let fps = AvmString::new_utf8(activation.gc(), "fps");
+            assert_eq!(camera.get_public_property(fps, activation)?, 30.into());
+            assert_eq!(
+                camera.get_public_property(istr!("width"), activation)?,
+                320.into()
+            );
+            assert_eq!(
+                camera.get_public_property(istr!("height"), activation)?,
+                240.into()
+            );
+
+            // Favoring the area again goes back to the full resolution at a lower frame rate.
+            camera.call_public_property(
+                set_mode,
+                &[640.into(), 480.into(), 30.into(), true.into()],
+                activation,
+            )?;
+            assert_eq!(camera.get_public_property(fps, activation)?, 15.into());
+            assert_eq!(
+                camera.get_public_property(istr!("width"), activation)?,
+                640.into()
+            );
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn invalid_mode_falls_back_to_default() {
+        let camera = ScriptedCamera::new(&[(640, 480)]).with_modes(MODES);
+        let builder = camera_player(camera);
+        with_avm2(builder, |activation| {
+            let camera = get_camera(activation, &[])?;
+
+            let set_mode = AvmString::new_utf8(activation.gc(), "setMode");
+            camera.call_public_property(
+                set_mode,
+                &[(-320).into(), 0.into(), f64::NAN.into()],
+                activation,
+            )?;
+
+            // The mode closest to 160x120 at 15fps is picked.
+            let fps = AvmString::new_utf8(activation.gc(), "fps");
+            assert_eq!(camera.get_public_property(fps, activation)?, 30.into());
+            assert_eq!(
+                camera.get_public_property(istr!("width"), activation)?,
+                160.into()
+            );
+            assert_eq!(
+                camera.get_public_property(istr!("height"), activation)?,
+                120.into()
+            );
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn preferred_pixel_format_is_requested_by_set_mode() {
+        let camera = ScriptedCamera::new(&[(640, 480)]).with_modes(MODES);
+        let preferred = camera.preferred_pixel_format.clone();
+        let builder = camera_player(camera);
+        with_avm2(builder, |activation| {
+            let camera = get_camera(activation, &[])?;
+            let object = camera.as_object().unwrap().as_camera().unwrap();
+            object.set_preferred_pixel_format(CameraPixelFormat::Yuyv);
+            assert_eq!(preferred.get(), CameraPixelFormat::Rgba);
+
+            let set_mode = AvmString::new_utf8(activation.gc(), "setMode");
+            camera.call_public_property(
+                set_mode,
+                &[320.into(), 240.into(), 30.into()],
+                activation,
+            )?;
+            assert_eq!(preferred.get(), CameraPixelFormat::Yuyv);
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn yuyv_frames_are_converted_to_rgba() {
+        let mut camera = ScriptedCamera::new(&[(2, 1)]);
+        // Black and white.
+        camera.frames = VecDeque::from([CameraFrame {
+            format: CameraFormat::new(2, 1),
+            pixel_format: CameraPixelFormat::Yuyv,
+            data: vec![16, 128, 235, 128],
+        }]);
+        let builder = camera_player(camera);
+        with_avm2(builder, |activation| {
+            let camera = get_camera(activation, &[])?;
+            let object = camera.as_object().unwrap().as_camera().unwrap();
+            assert_eq!(object.pixel_format(), CameraPixelFormat::Rgba);
+
+            Cameras::update_cameras(activation.context);
+            assert_eq!(object.pixel_format(), CameraPixelFormat::Yuyv);
+            assert_eq!(
+                *object.pixels(),
+                Some(vec![0, 0, 0, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF])
+            );
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn set_mode_without_modes_keeps_native_format() {
+        let camera = ScriptedCamera::new(&[(352, 288)]);
+        let builder = camera_player(camera);
+        with_avm2(builder, |activation| {
+            let camera = get_camera(activation, &[])?;
+
+            let set_mode = AvmString::new_utf8(activation.gc(), "setMode");
+            camera.call_public_property(
+                set_mode,
+                &[640.into(), 480.into(), 30.into(), false.into()],
+                activation,
+            )?;
+
+            assert_eq!(
+                camera.get_public_property(istr!("width"), activation)?,
+                352.into()
+            );
+            assert_eq!(
+                camera.get_public_property(istr!("height"), activation)?,
+                288.into()
+            );
+
+            Ok(())
+        });
+    }
+
+    /// Reads `Camera.isSupported` and `Camera.names`, and the name of the camera
+    /// returned by `Camera.getCamera`.
+    fn enumerate_cameras(player: &mut Player) -> (bool, Vec<String>, Option<String>) {
+        player.mutate_with_update_context(|context| {
+            let activation = &mut Activation::from_nothing(context);
+            let camera_class: Value = activation.avm2().classes().camera.into();
+
+            let is_supported = AvmString::new_utf8(activation.gc(), "isSupported");
+            let is_supported = camera_class
+                .get_public_property(is_supported, activation)
+                .unwrap()
+                .coerce_to_boolean();
+
+            let names = AvmString::new_utf8(activation.gc(), "names");
+            let names = camera_class.get_public_property(names, activation).unwrap();
+            let names = array_values(names)
+                .into_iter()
+                .map(|name| name.coerce_to_string(activation).unwrap().to_string())
+                .collect();
+
+            let camera = get_camera(activation, &[])
+                .unwrap()
+                .as_object()
+                .and_then(|camera| camera.as_camera())
+                .map(|camera| camera.name().clone());
+
+            (is_supported, names, camera)
+        })
+    }
+
+    fn array_values<'gc>(value: Value<'gc>) -> Vec<Value<'gc>> {
+        let array = value.as_object().unwrap();
+        let array = array.as_array_storage().unwrap();
+        let values = array.iter().map(|val| val.unwrap()).collect();
+        values
+    }
+
+    #[test]
+    fn backend_can_be_swapped() {
+        let player = camera_player(ScriptedCamera::new(&[(320, 240)])).build();
+        let mut player = player.lock().unwrap();
+        assert_eq!(
+            enumerate_cameras(&mut player),
+            (
+                true,
+                vec!["Scripted Camera".to_string()],
+                Some("Scripted Camera".to_string())
+            )
+        );
+
+        let replacement = ScriptedCamera::new(&[(320, 240)]).with_device(CameraDevice {
+            id: 7,
+            name: "Replacement Camera".to_string(),
+        });
+        player.set_camera(Box::new(replacement));
+        assert_eq!(
+            enumerate_cameras(&mut player),
+            (
+                true,
+                vec!["Replacement Camera".to_string()],
+                Some("Replacement Camera".to_string())
+            )
+        );
+
+        player.set_camera(Box::new(NullCameraBackend::new()));
+        assert_eq!(enumerate_cameras(&mut player), (false, vec![], None));
+    }
+
+    #[test]
+    fn is_supported_matches_names_with_sparse_ids() {
+        // Like `/dev/video2` and `/dev/video5`, with the nodes in between missing.
+        let device = |id, name: &str| {
+            (
+                CameraDevice {
+                    id,
+                    name: name.to_string(),
+                },
+                CameraFormat::new(320, 240),
+            )
+        };
+        let camera = MultiCamera {
+            devices: vec![device(2, "Front"), device(5, "Back")],
+            open: SlotMap::with_key(),
+        };
+        let player = camera_player(camera).build();
+        let mut player = player.lock().unwrap();
+        assert_eq!(
+            enumerate_cameras(&mut player),
+            (
+                true,
+                vec!["Front".to_string(), "Back".to_string()],
+                Some("Front".to_string())
+            )
+        );
+    }
+
+    #[test]
+    fn index_selects_listed_device_not_node() {
+        // Like `/dev/video0` and `/dev/video3`, with the nodes in between missing.
+        let device = |id, name: &str| {
+            (
+                CameraDevice {
+                    id,
+                    name: name.to_string(),
+                },
+                CameraFormat::new(320, 240),
+            )
+        };
+        let camera = MultiCamera {
+            devices: vec![device(0, "Front"), device(3, "Back")],
+            open: SlotMap::with_key(),
+        };
+        let builder = camera_player(camera);
+        with_avm2(builder, |activation| {
+            let index = AvmString::new_utf8(activation.gc(), "1");
+            let camera = get_camera(activation, &[index.into()])?;
+            let camera = camera.as_object().unwrap().as_camera().unwrap();
+            assert_eq!(camera.device_id(), 3);
+            assert_eq!(camera.name().as_str(), "Back");
+
+            // There are only two devices, so there's nothing at index 3.
+            let index = AvmString::new_utf8(activation.gc(), "3");
+            assert_eq!(get_camera(activation, &[index.into()])?, Value::Null);
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn frozen_camera_keeps_last_frame() {
+        let camera = ScriptedCamera::new(&[(320, 240), (320, 240), (320, 240)]);
+        let delivered = camera.delivered.clone();
+        let builder = camera_player(camera);
+        with_avm2(builder, |activation| {
+            let (camera, received) = camera_with_events(activation, "activity")?;
+            let object = camera.as_object().unwrap().as_camera().unwrap();
+
+            Cameras::update_cameras(activation.context);
+            assert_eq!(delivered.get(), 1);
+
+            let freeze = AvmString::new_utf8(activation.gc(), "freeze");
+            camera.call_public_property(freeze, &[], activation)?;
+            Cameras::update_cameras(activation.context);
+            Cameras::update_cameras(activation.context);
+            assert_eq!(delivered.get(), 1);
+            let frame = object.frame().expect("Frozen frame should be kept");
+            assert_eq!((frame.width, frame.height), (320, 240));
+
+            let resume = AvmString::new_utf8(activation.gc(), "resume");
+            camera.call_public_property(resume, &[], activation)?;
+            Cameras::update_cameras(activation.context);
+            assert_eq!(delivered.get(), 2);
+
+            let activating = AvmString::new_utf8(activation.gc(), "activating");
+            let events = array_values(received);
+            assert_eq!(events.len(), 2);
+            assert_eq!(
+                events[0].get_public_property(activating, activation)?,
+                false.into()
+            );
+            assert_eq!(
+                events[1].get_public_property(activating, activation)?,
+                true.into()
+            );
+
+            Ok(())
+        });
+    }
+
+    /// Devices that each capture into their own queue of frames, in a fixed format.
+    struct MultiCamera {
+        devices: Vec<(CameraDevice, CameraFormat)>,
+        open: SlotMap<CameraHandle, (CameraFormat, VecDeque<CameraFrame>)>,
+    }
+
+    impl CameraBackend for MultiCamera {
+        fn list_devices(&mut self) -> Vec<CameraDevice> {
+            self.devices
+                .iter()
+                .map(|(device, _)| device.clone())
+                .collect()
+        }
+
+        fn open(&mut self, id: u32) -> Result<CameraHandle, CameraError> {
+            let (_, format) = self
+                .devices
+                .iter()
+                .find(|(device, _)| device.id == id)
+                .ok_or(CameraError::NotFound)?;
+            let frame = CameraFrame::rgba(
+                *format,
+                vec![id as u8; (format.width * format.height * 4) as usize],
+            );
+            let frames = VecDeque::from([frame.clone(), frame]);
+            Ok(self.open.insert((*format, frames)))
+        }
+
+        fn close(&mut self, handle: CameraHandle) {
+            self.open.remove(handle);
+        }
+
+        fn format(&self, handle: CameraHandle) -> Option<CameraFormat> {
+            self.open.get(handle).map(|(format, _)| *format)
+        }
+
+        fn supported_modes(&self, _handle: CameraHandle) -> Vec<CameraMode> {
+            Vec::new()
+        }
+
+        fn set_mode(
+            &mut self,
+            _handle: CameraHandle,
+            _mode: CameraMode,
+        ) -> Result<(), CameraError> {
+            Err(CameraError::Other("Unsupported".to_string()))
+        }
+
+        fn set_frozen(&mut self, _handle: CameraHandle, _frozen: bool) {}
+
+        fn current_fps(&self, _handle: CameraHandle) -> f64 {
+            0.0
+        }
+
+        fn poll_event(&mut self, _handle: CameraHandle) -> Option<CameraEvent> {
+            None
+        }
+
+        fn next_frame(&mut self, handle: CameraHandle) -> Option<CameraFrame> {
+            self.open.get_mut(handle)?.1.pop_front()
+        }
+    }
+
+    #[test]
+    fn cameras_capture_independently() {
+        let camera = MultiCamera {
+            devices: vec![
+                (
+                    CameraDevice {
+                        id: 0,
+                        name: "Front".to_string(),
+                    },
+                    CameraFormat::new(320, 240),
+                ),
+                (
+                    CameraDevice {
+                        id: 1,
+                        name: "Back".to_string(),
+                    },
+                    CameraFormat::new(640, 480),
+                ),
+            ],
+            open: SlotMap::with_key(),
+        };
+        let builder = camera_player(camera);
+        with_avm2(builder, |activation| {
+            let index = AvmString::new_utf8(activation.gc(), "0");
+            let front = get_camera(activation, &[index.into()])?;
+            let index = AvmString::new_utf8(activation.gc(), "1");
+            let back = get_camera(activation, &[index.into()])?;
+            let front = front.as_object().unwrap().as_camera().unwrap();
+            let back = back.as_object().unwrap().as_camera().unwrap();
+            assert_ne!(front.handle(), back.handle());
+            assert_eq!((front.index(), front.device_id()), (Some(0), 0));
+            assert_eq!((back.index(), back.device_id()), (Some(1), 1));
+
+            Cameras::update_cameras(activation.context);
+            let frame = front.frame().expect("Front camera should deliver a frame");
+            assert_eq!((frame.width, frame.height), (320, 240));
+            let frame = back.frame().expect("Back camera should deliver a frame");
+            assert_eq!((frame.width, frame.height), (640, 480));
+
+            // Closing one camera doesn't affect the other.
+            let handle = front.handle().unwrap();
+            activation.context.camera.close(handle);
+            assert!(activation.context.camera.next_frame(handle).is_none());
+            let handle = back.handle().unwrap();
+            assert!(activation.context.camera.next_frame(handle).is_some());
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn mirroring_reverses_columns() {
+        // A 3x2 frame, where every pixel has distinct channels.
+        let mut rgba: Vec<u8> = (0..24).collect();
+        mirror_horizontally(&mut rgba, 3);
+        assert_eq!(
+            rgba,
+            vec![
+                8, 9, 10, 11, 4, 5, 6, 7, 0, 1, 2, 3, //
+                20, 21, 22, 23, 16, 17, 18, 19, 12, 13, 14, 15,
+            ]
+        );
+
+        // Mirroring twice restores the original frame.
+        mirror_horizontally(&mut rgba, 3);
+        assert_eq!(rgba, (0..24).collect::<Vec<u8>>());
+    }
+
+    #[test]
+    fn index_refers_to_selected_device() {
+        let camera = MultiCamera {
+            devices: vec![
+                (
+                    CameraDevice {
+                        id: 4,
+                        name: "Front".to_string(),
+                    },
+                    CameraFormat::new(320, 240),
+                ),
+                (
+                    CameraDevice {
+                        id: 9,
+                        name: "Back".to_string(),
+                    },
+                    CameraFormat::new(640, 480),
+                ),
+            ],
+            open: SlotMap::with_key(),
+        };
+        let builder = camera_player(camera);
+        with_avm2(builder, |activation| {
+            let index = AvmString::new_utf8(activation.gc(), "index");
+
+            for (position, name, device_id) in [(0, "Front", 4), (1, "Back", 9)] {
+                let request = AvmString::new_utf8(activation.gc(), position.to_string());
+                let camera = get_camera(activation, &[request.into()])?;
+                let object = camera.as_object().unwrap().as_camera().unwrap();
+                assert_eq!(object.device_id(), device_id);
+                assert_eq!(
+                    camera
+                        .get_public_property(index, activation)?
+                        .coerce_to_i32(activation)?,
+                    position
+                );
+                assert_eq!(
+                    camera.get_public_property(istr!("name"), activation)?,
+                    AvmString::new_utf8(activation.gc(), name).into()
+                );
+            }
+
+            // Asking for the same device again returns the same instance.
+            let position = AvmString::new_utf8(activation.gc(), "1");
+            let first = get_camera(activation, &[position.into()])?;
+            let second = get_camera(activation, &[position.into()])?;
+            assert_eq!(first, second);
+
+            Ok(())
+        });
+    }
+
+    /// Does what `Player::set_camera_permission` does, without leaving the activation.
+    fn answer_permission_prompt(activation: &mut Activation, permission: CameraPermission) {
+        let previous = std::mem::replace(&mut activation.context.camera_permission, permission);
+        Cameras::resolve_permission(activation.context, previous);
+    }
+
+    #[test]
+    fn answering_prompt_unmutes_camera() {
+        let camera = ScriptedCamera::new(&[(320, 240)]);
+        let builder = camera_player(camera).with_camera_permission(CameraPermission::Prompt);
+        with_avm2(builder, |activation| {
+            let (camera, received) = camera_with_events(activation, "status")?;
+            let object = camera.as_object().unwrap().as_camera().unwrap();
+            assert!(object.muted());
+            assert!(object.handle().is_none());
+
+            answer_permission_prompt(activation, CameraPermission::Granted);
+            assert!(!object.muted());
+            assert!(object.handle().is_some());
+
+            answer_permission_prompt(activation, CameraPermission::Denied);
+            assert!(object.muted());
+            assert!(object.handle().is_none());
+
+            let mut codes = Vec::new();
+            for event in array_values(received) {
+                codes.push(status_of(activation, event)?.0);
+            }
+            assert_eq!(codes, ["Camera.Unmuted", "Camera.Muted"]);
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn activity_times_out_without_motion() {
+        let mut detector = ActivityDetector::default();
+        let timeout = Duration::from_millis(2000);
+        let start = Instant::now();
+
+        let black = vec![0; 4 * 4];
+        let white = vec![0xFF; 4 * 4];
+        assert_eq!(detector.measure(&black), 0.0);
+        assert_eq!(detector.update(Some(0.0), 50, timeout, start), None);
+
+        let level = detector.measure(&white);
+        assert_eq!(level, 100.0);
+        assert_eq!(detector.update(Some(level), 50, timeout, start), Some(true));
+        assert_eq!(detector.update(Some(level), 50, timeout, start), None);
+
+        // A still image doesn't count as motion, but it takes a while to become inactive.
+        let level = detector.measure(&white);
+        assert_eq!(level, 0.0);
+        let later = start + Duration::from_millis(1000);
+        assert_eq!(detector.update(Some(level), 50, timeout, later), None);
+        let later = start + Duration::from_millis(2000);
+        assert_eq!(detector.update(None, 50, timeout, later), Some(false));
+        assert_eq!(detector.update(None, 50, timeout, later), None);
+
+        // Nothing is ever motion at level 100.
+        let level = detector.measure(&black);
+        assert_eq!(detector.update(Some(level), 100, timeout, later), None);
+    }
+
+    #[test]
+    fn stalled_frames_deactivate_until_next_frame() {
+        let mut detector = ActivityDetector::default();
+        let timeout = Duration::from_millis(2000);
+        let start = Instant::now();
+
+        // At motion level 0, every frame counts as activity.
+        let level = detector.measure(&[0; 4 * 4]);
+        assert_eq!(detector.update(Some(level), 0, timeout, start), Some(true));
+
+        // The device stops delivering frames.
+        let later = start + Duration::from_millis(1999);
+        assert_eq!(detector.update(None, 0, timeout, later), None);
+        let later = start + Duration::from_millis(2000);
+        assert_eq!(detector.update(None, 0, timeout, later), Some(false));
+
+        let level = detector.measure(&[0; 4 * 4]);
+        assert_eq!(detector.update(Some(level), 0, timeout, later), Some(true));
+    }
+
+    #[test]
+    fn flowing_frames_activate_camera() {
+        let camera = ScriptedCamera::new(&[(320, 240)]);
+        let builder = camera_player(camera);
+        with_avm2(builder, |activation| {
+            let (camera, received) = camera_with_events(activation, "activity")?;
+
+            // At motion level 0, any captured frame counts as activity.
+            let set_motion_level = AvmString::new_utf8(activation.gc(), "setMotionLevel");
+            camera.call_public_property(set_motion_level, &[0.into(), 500.into()], activation)?;
+            let motion_timeout = AvmString::new_utf8(activation.gc(), "motionTimeout");
+            assert_eq!(
+                camera.get_public_property(motion_timeout, activation)?,
+                500.into()
+            );
+
+            let motion_level = AvmString::new_utf8(activation.gc(), "motionLevel");
+            assert_eq!(
+                camera.get_public_property(motion_level, activation)?,
+                0.into()
+            );
+
+            Cameras::update_cameras(activation.context);
+            let activity_level = AvmString::new_utf8(activation.gc(), "activityLevel");
+            assert_eq!(
+                camera.get_public_property(activity_level, activation)?,
+                0.into()
+            );
+            let events = array_values(received);
+            assert_eq!(events.len(), 1);
+            let activating = AvmString::new_utf8(activation.gc(), "activating");
+            assert_eq!(
+                events[0].get_public_property(activating, activation)?,
+                true.into()
+            );
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn capture_threads_stop_with_player() {
+        let camera = ScriptedCamera::new(&[(1, 1)]).threaded();
+        let running = camera.running.clone();
+        let player = camera_player(camera).build();
+
+        enumerate_cameras(&mut player.lock().unwrap());
+        assert_eq!(running.load(Ordering::SeqCst), 1);
+
+        drop(player);
+        assert_eq!(running.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn devices_are_listed_once_in_quick_succession() {
+        let camera = ScriptedCamera::new(&[(320, 240)]);
+        let enumerations = camera.enumerations.clone();
+        let player = camera_player(camera).build();
+        let mut player = player.lock().unwrap();
+
+        enumerate_cameras(&mut player);
+        assert_eq!(enumerations.get(), 1);
+
+        player.mutate_with_update_context(|context| context.cameras.invalidate_devices());
+        enumerate_cameras(&mut player);
+        assert_eq!(enumerations.get(), 2);
+    }
+
+    #[test]
+    fn camera_can_be_attached_to_video() {
+        let camera = ScriptedCamera::new(&[(320, 240)]);
+        let builder = camera_player(camera);
+        with_avm2(builder, |activation| {
+            let camera = get_camera(activation, &[])?;
+
+            let video = activation
+                .avm2()
+                .classes()
+                .video
+                .construct(activation, &[])?;
+            let attach_camera = AvmString::new_utf8(activation.gc(), "attachCamera");
+            video.call_public_property(attach_camera, &[camera], activation)?;
+
+            // The video displays the camera's texture, which exists once a frame arrived.
+            Cameras::update_cameras(activation.context);
+            let object = camera.as_object().unwrap().as_camera().unwrap();
+            assert!(object.frame().is_some());
+
+            video.call_public_property(attach_camera, &[Value::Null], activation)?;
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn identical_names_are_disambiguated() {
+        let device = |id, name: &str| CameraDevice {
+            id,
+            name: name.to_string(),
+        };
+        let mut devices = vec![
+            device(0, "Webcam"),
+            device(1, "Other"),
+            device(2, "Webcam"),
+            device(3, "Webcam"),
+        ];
+        disambiguate_names(&mut devices);
+        let names: Vec<_> = devices.iter().map(|device| device.name.as_str()).collect();
+        assert_eq!(names, ["Webcam", "Other", "Webcam #2", "Webcam #3"]);
+    }
+
+    #[test]
+    fn busy_camera_reports_error() {
+        let builder = camera_player(ScriptedCamera::new(&[(320, 240)]).busy());
+        with_avm2(builder, |activation| {
+            let (camera, received) = camera_with_events(activation, "status")?;
+            let object = camera.as_object().unwrap().as_camera().unwrap();
+            assert!(object.handle().is_none());
+            assert!(!object.muted());
+
+            Cameras::update_cameras(activation.context);
+            Cameras::update_cameras(activation.context);
+            let events = array_values(received);
+            assert_eq!(events.len(), 1);
+            assert_eq!(
+                status_of(activation, events[0])?,
+                ("Camera.Error".to_string(), "error".to_string())
+            );
+
+            // Opening the device is attempted again by the next call.
+            let again = get_camera(activation, &[])?;
+            assert!(!Object::ptr_eq(
+                again.as_object().unwrap().as_camera().unwrap(),
+                object
+            ));
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn drawing_reopens_failed_camera() {
+        let camera = ScriptedCamera::new(&[(2, 2)]).busy();
+        let busy = camera.busy.clone();
+        let builder = camera_player(camera);
+        with_avm2(builder, |activation| {
+            let camera = get_camera(activation, &[])?;
+            let object = camera.as_object().unwrap().as_camera().unwrap();
+            assert!(object.handle().is_none());
+
+            let bitmap_data: Value = activation
+                .avm2()
+                .classes()
+                .bitmapdata
+                .construct(activation, &[2.into(), 2.into(), true.into(), 0.into()])?;
+            let draw_to_bitmap_data = AvmString::new_utf8(activation.gc(), "drawToBitmapData");
+            camera.call_public_property(draw_to_bitmap_data, &[bitmap_data], activation)?;
+            assert!(object.handle().is_none());
+
+            // Once the device is free, it's opened and kept open for the next frames.
+            busy.set(false);
+            camera.call_public_property(draw_to_bitmap_data, &[bitmap_data], activation)?;
+            assert!(object.handle().is_some());
+            let again = get_camera(activation, &[])?;
+            assert!(Object::ptr_eq(
+                again.as_object().unwrap().as_camera().unwrap(),
+                object
+            ));
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn unplugged_camera_reports_muted() {
+        let camera = ScriptedCamera::new(&[(320, 240)])
+            .with_current_fps(30.0)
+            .unplugged_after_playback();
+        let builder = camera_player(camera);
+        with_avm2(builder, |activation| {
+            let (camera, received) = camera_with_events(activation, "status")?;
+            let object = camera.as_object().unwrap().as_camera().unwrap();
+
+            let current_fps = AvmString::new_utf8(activation.gc(), "currentFPS");
+            Cameras::update_cameras(activation.context);
+            assert!(object.frame().is_some());
+            assert_eq!(
+                camera.get_public_property(current_fps, activation)?,
+                30.into()
+            );
+
+            Cameras::update_cameras(activation.context);
+            assert!(object.handle().is_none());
+            assert!(object.frame().is_none());
+            assert!(object.muted());
+            assert_eq!(
+                camera.get_public_property(current_fps, activation)?,
+                0.into()
+            );
+
+            let events = array_values(received);
+            assert_eq!(events.len(), 1);
+            assert_eq!(
+                status_of(activation, events[0])?,
+                ("Camera.Muted".to_string(), "status".to_string())
+            );
+
+            // The device is opened again once it comes back.
+            let camera = get_camera(activation, &[])?;
+            let reopened = camera.as_object().unwrap().as_camera().unwrap();
+            assert!(!Object::ptr_eq(object, reopened));
+            assert!(reopened.handle().is_some());
+
+            Ok(())
+        });
+    }
+}
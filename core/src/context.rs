@@ -12,11 +12,14 @@ use crate::avm2::TObject as _;
 use crate::avm2::{Avm2, Object as Avm2Object, SoundChannelObject};
 use crate::backend::{
     audio::{AudioBackend, AudioManager, SoundHandle, SoundInstanceHandle},
+    camera::{CameraBackend, CameraPermission},
     log::LogBackend,
+    microphone::MicrophoneBackend,
     navigator::NavigatorBackend,
     storage::StorageBackend,
     ui::UiBackend,
 };
+use crate::camera::Cameras;
 use crate::context_menu::ContextMenuState;
 use crate::display_object::{EditText, MovieClip, SoundTransform, Stage};
 use crate::events::PlayerNotification;
@@ -27,6 +30,7 @@ use crate::input::InputManager;
 use crate::library::Library;
 use crate::loader::LoadManager;
 use crate::local_connection::LocalConnections;
+use crate::microphone::Microphones;
 use crate::net_connection::NetConnections;
 use crate::player::PostFrameCallback;
 use crate::player::{MouseData, Player};
@@ -99,6 +103,15 @@ pub struct UpdateContext<'gc> {
     /// The audio manager, managing all actively playing sounds.
     pub audio_manager: &'gc mut AudioManager<'gc>,
 
+    /// The camera backend, used to capture video from the user's cameras.
+    pub camera: &'gc mut dyn CameraBackend,
+
+    /// Whether the movie may capture from the user's cameras.
+    pub camera_permission: CameraPermission,
+
+    /// The microphone backend, used to find the user's microphones.
+    pub microphone: &'gc mut dyn MicrophoneBackend,
+
     /// The navigator backend, used by the AVM to make HTTP requests and visit webpages.
     pub navigator: &'gc mut dyn NavigatorBackend,
 
@@ -222,6 +235,12 @@ pub struct UpdateContext<'gc> {
 
     pub local_connections: &'gc mut LocalConnections<'gc>,
 
+    /// List of active Camera objects.
+    pub cameras: &'gc mut Cameras<'gc>,
+
+    /// List of Microphone objects that are capturing audio.
+    pub microphones: &'gc mut Microphones<'gc>,
+
     /// Dynamic root for allowing handles to GC objects to exist outside of the GC.
     pub dynamic_root: gc_arena::DynamicRootSet<'gc>,
 
@@ -1,10 +1,12 @@
 //! Video player display object
 
 use crate::avm1::{NativeObject as Avm1NativeObject, Object as Avm1Object, Value as Avm1Value};
+use crate::avm2::object::CameraObject;
 use crate::avm2::{
     Activation as Avm2Activation, Object as Avm2Object, StageObject as Avm2StageObject,
     Value as Avm2Value,
 };
+use crate::backend::camera::CameraFormat;
 use crate::context::{RenderContext, UpdateContext};
 use crate::display_object::{Avm1TextFieldBinding, DisplayObjectBase, DisplayObjectPtr};
 use crate::prelude::*;
@@ -109,6 +111,11 @@ pub enum VideoSource<'gc> {
         /// The stream the video is downloaded from.
         stream: NetStream<'gc>,
     },
+    /// An attached Camera.
+    Camera {
+        /// The camera whose most recently captured frame is displayed.
+        camera: CameraObject<'gc>,
+    },
     Unconnected,
 }
 
@@ -203,6 +210,23 @@ impl<'gc> Video<'gc> {
         self.0.keyframes.replace(BTreeSet::new());
     }
 
+    /// Convert this Video into a Camera sourced video.
+    ///
+    /// Existing video state related to the old video stream will be dropped.
+    pub fn attach_camera(self, context: &mut UpdateContext<'gc>, camera: CameraObject<'gc>) {
+        self.set_source(context, VideoSource::Camera { camera });
+        self.0.stream.set(VideoStream::Uninstantiated(0));
+        self.0.keyframes.replace(BTreeSet::new());
+    }
+
+    /// Disconnect this Video from whatever it was displaying, and clear its image.
+    pub fn detach(self, context: &mut UpdateContext<'gc>) {
+        self.set_source(context, VideoSource::Unconnected);
+        self.0.decoded_frame.replace(None);
+        self.0.stream.set(VideoStream::Uninstantiated(0));
+        self.0.keyframes.replace(BTreeSet::new());
+    }
+
     /// Preload frame data from an SWF.
     ///
     /// This function yields an error if this video player is not playing an
@@ -222,6 +246,7 @@ impl<'gc> Video<'gc> {
                 frames.insert(tag.frame_num.into(), (subslice.start, subslice.end));
             }
             VideoSource::NetStream { .. } => {}
+            VideoSource::Camera { .. } => {}
             VideoSource::Unconnected { .. } => {}
         }
     }
@@ -245,6 +270,7 @@ impl<'gc> Video<'gc> {
         let num_frames = match self.0.source.get() {
             VideoSource::Swf(swf_source) => swf_source.streamdef.num_frames as usize,
             VideoSource::NetStream { .. } => return,
+            VideoSource::Camera { .. } => return,
             VideoSource::Unconnected { .. } => return,
         };
 
@@ -331,6 +357,7 @@ impl<'gc> Video<'gc> {
                 }
             },
             VideoSource::NetStream { .. } => return,
+            VideoSource::Camera { .. } => return,
             VideoSource::Unconnected { .. } => return,
         };
 
@@ -428,6 +455,7 @@ impl<'gc> TDisplayObject<'gc> for Video<'gc> {
                 }
             }
             VideoSource::NetStream { .. } => return,
+            VideoSource::Camera { .. } => return,
             VideoSource::Unconnected { .. } => return,
         };
 
@@ -484,6 +512,7 @@ impl<'gc> TDisplayObject<'gc> for Video<'gc> {
         match self.0.source.get() {
             VideoSource::Swf(swf_source) => swf_source.streamdef.id,
             VideoSource::NetStream { .. } => 0,
+            VideoSource::Camera { .. } => 0,
             VideoSource::Unconnected { .. } => 0,
         }
     }
@@ -508,6 +537,10 @@ impl<'gc> TDisplayObject<'gc> for Video<'gc> {
 
         let mut transform = context.transform_stack.transform();
         let bounds = self.self_bounds();
+        let camera_format = match self.0.source.get() {
+            VideoSource::Camera { camera } => Some(camera.format()),
+            _ => None,
+        };
 
         // TODO: smoothing flag should be a video property
         let (smoothed_flag, num_frames, version, decoded_frame, codec) = match self.0.source.get() {
@@ -525,6 +558,11 @@ impl<'gc> TDisplayObject<'gc> for Video<'gc> {
                 stream.last_decoded_bitmap(),
                 None,
             ),
+            VideoSource::Camera { camera } => match camera.frame() {
+                Some(frame) => (false, None, self.0.movie.version(), Some(frame), None),
+                // Muted cameras, and ones that didn't capture anything yet, show nothing.
+                None => return context.transform_stack.pop(),
+            },
             VideoSource::Unconnected { .. } => return context.transform_stack.pop(),
         };
 
@@ -539,10 +577,18 @@ impl<'gc> TDisplayObject<'gc> for Video<'gc> {
         if let Some(bitmap) = decoded_frame {
             // The actual decoded frames might be different in size than the declared
             // bounds of the VideoStream tag, so a final scale adjustment has to be done.
-            transform.matrix *= Matrix::scale(
-                bounds.width().to_pixels() as f32 / bitmap.width as f32,
-                bounds.height().to_pixels() as f32 / bitmap.height as f32,
+            let video_size = (
+                bounds.width().to_pixels() as f32,
+                bounds.height().to_pixels() as f32,
             );
+            let frame_size = (bitmap.width.into(), bitmap.height.into());
+            transform.matrix *= match camera_format {
+                Some(format) => camera_frame_matrix(video_size, frame_size, format),
+                None => Matrix::scale(
+                    video_size.0 / bitmap.width as f32,
+                    video_size.1 / bitmap.height as f32,
+                ),
+            };
 
             context.commands.render_bitmap(
                 bitmap.handle,
@@ -604,3 +650,65 @@ impl<'gc> TDisplayObject<'gc> for Video<'gc> {
             })
     }
 }
+
+/// Scales a captured camera frame into a Video of the given size.
+///
+/// Like in Flash Player, frames are stretched to fill the Video. Flash Player doesn't
+/// know about non-square pixels though, so frames that have them are instead fitted
+/// into the Video at their display size and centered, so that they don't look squashed.
+fn camera_frame_matrix(
+    video_size: (f32, f32),
+    frame_size: (u32, u32),
+    format: CameraFormat,
+) -> Matrix {
+    let (video_width, video_height) = video_size;
+    let (frame_width, frame_height) = (frame_size.0 as f32, frame_size.1 as f32);
+    let (display_width, display_height) = format.display_size();
+    if (display_width, display_height) == (format.width, format.height)
+        || display_width == 0
+        || display_height == 0
+    {
+        return Matrix::scale(video_width / frame_width, video_height / frame_height);
+    }
+
+    let scale = (video_width / display_width as f32).min(video_height / display_height as f32);
+    let width = display_width as f32 * scale;
+    let height = display_height as f32 * scale;
+    Matrix::create_box(
+        width / frame_width,
+        height / frame_height,
+        Twips::from_pixels(((video_width - width) / 2.0).into()),
+        Twips::from_pixels(((video_height - height) / 2.0).into()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn square_camera_pixels_are_stretched() {
+        let matrix = camera_frame_matrix((320.0, 180.0), (640, 480), CameraFormat::new(640, 480));
+        assert_eq!(matrix, Matrix::scale(0.5, 0.375));
+    }
+
+    #[test]
+    fn non_square_camera_pixels_keep_their_display_aspect() {
+        let format = CameraFormat {
+            width: 720,
+            height: 576,
+            pixel_aspect_ratio: (16, 15),
+        };
+
+        // The frame is displayed as 768x576, which exactly fills a 4:3 Video.
+        let matrix = camera_frame_matrix((768.0, 576.0), (720, 576), format);
+        assert_eq!(matrix, Matrix::scale(768.0 / 720.0, 1.0));
+
+        // In a Video of the captured size, it's shrunk to 720x540 and centered.
+        let matrix = camera_frame_matrix((720.0, 576.0), (720, 576), format);
+        assert_eq!(
+            matrix,
+            Matrix::create_box(1.0, 540.0 / 576.0, Twips::ZERO, Twips::from_pixels(18.0))
+        );
+    }
+}
@@ -18,6 +18,7 @@ mod avm2;
 mod binary_data;
 pub mod bitmap;
 pub mod buffer;
+mod camera;
 mod character;
 pub mod context;
 pub mod context_menu;
@@ -34,6 +35,7 @@ pub mod limits;
 pub mod loader;
 mod local_connection;
 mod locale;
+mod microphone;
 mod net_connection;
 pub mod pixel_bender;
 mod player;
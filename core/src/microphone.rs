@@ -0,0 +1,374 @@
+//! Management of capturing microphones
+
+use crate::avm2::bytearray::ByteArrayStorage;
+use crate::avm2::globals::slots::flash_media_microphone as microphone_slots;
+use crate::avm2::object::{ByteArrayObject, EventObject, Object, TObject};
+use crate::avm2::{Activation, Avm2};
+use crate::backend::camera::CameraPermission;
+use crate::backend::microphone::{MicrophoneDevice, MicrophoneHandle};
+use crate::camera::DEVICES_TTL;
+use crate::context::UpdateContext;
+use gc_arena::Collect;
+use ruffle_macros::istr;
+use web_time::Instant;
+
+#[derive(Clone, Collect)]
+#[collect(no_drop)]
+struct ActiveMicrophone<'gc> {
+    #[collect(require_static)]
+    handle: MicrophoneHandle,
+
+    object: Object<'gc>,
+
+    /// The number of samples that were delivered in `sampleData` events so far.
+    position: f64,
+}
+
+/// Manages the microphones that are capturing audio, for `Microphone.activityLevel`
+/// and the `sampleData` events.
+#[derive(Collect)]
+#[collect(no_drop)]
+pub struct Microphones<'gc> {
+    microphones: Vec<ActiveMicrophone<'gc>>,
+
+    /// The devices listed by the backend, and when they were listed.
+    #[collect(require_static)]
+    devices: Option<(Vec<MicrophoneDevice>, Instant)>,
+}
+
+impl<'gc> Microphones<'gc> {
+    pub fn empty() -> Self {
+        Self {
+            microphones: Vec::new(),
+            devices: None,
+        }
+    }
+
+    /// Lists the devices of the microphone backend.
+    ///
+    /// Like with `Cameras::list_devices`, the list is reused for a short while, as
+    /// movies tend to read `Microphone.names` and call `Microphone.getMicrophone` in
+    /// quick succession.
+    pub fn list_devices(context: &mut UpdateContext<'gc>) -> Vec<MicrophoneDevice> {
+        let now = Instant::now();
+        if let Some((devices, listed)) = &context.microphones.devices {
+            if now.duration_since(*listed) < DEVICES_TTL {
+                return devices.clone();
+            }
+        }
+        let devices = context.microphone.list_devices();
+        context.microphones.devices = Some((devices.clone(), now));
+        devices
+    }
+
+    /// Forgets the listed devices, so that the next `list_devices` asks the backend again.
+    pub fn invalidate_devices(&mut self) {
+        self.devices = None;
+    }
+
+    /// Starts capturing from the device of a `Microphone`, unless it already is.
+    ///
+    /// Like in Flash Player, the privacy settings for cameras also apply to microphones.
+    pub fn start_capture(
+        context: &mut UpdateContext<'gc>,
+        object: Object<'gc>,
+        device_id: u32,
+        sample_rate: u32,
+    ) {
+        if context.camera_permission != CameraPermission::Granted {
+            return;
+        }
+        let microphones = &context.microphones.microphones;
+        if microphones
+            .iter()
+            .any(|active| Object::ptr_eq(active.object, object))
+        {
+            return;
+        }
+
+        match context.microphone.open(device_id, sample_rate) {
+            Ok(handle) => context.microphones.microphones.push(ActiveMicrophone {
+                handle,
+                object,
+                position: 0.0,
+            }),
+            Err(e) => tracing::warn!("Failed to open microphone {device_id}: {e}"),
+        }
+    }
+
+    /// Stops capturing from every microphone, and forgets about the listed devices.
+    pub fn close_all(context: &mut UpdateContext<'gc>) {
+        context.microphones.invalidate_devices();
+        for microphone in context.microphones.microphones.drain(..) {
+            context.microphone.close(microphone.handle);
+        }
+    }
+
+    /// Picks up the audio captured by every active microphone, updating its
+    /// `activityLevel` and dispatching it in a `sampleData` event.
+    pub fn update_microphones(context: &mut UpdateContext<'gc>) {
+        for index in 0..context.microphones.microphones.len() {
+            let ActiveMicrophone {
+                handle,
+                object,
+                position,
+            } = context.microphones.microphones[index].clone();
+            let samples = context.microphone.take_samples(handle);
+            if samples.is_empty() {
+                continue;
+            }
+            context.microphones.microphones[index].position += samples.len() as f64;
+
+            let mut activation = Activation::from_nothing(context);
+            object
+                .set_slot(
+                    microphone_slots::_ACTIVITY_LEVEL,
+                    activity_level(&samples).into(),
+                    &mut activation,
+                )
+                .expect("activityLevel is a Number");
+
+            // Samples are 32-bit floats, in the default big endian order of ByteArrays.
+            let mut storage = ByteArrayStorage::new();
+            for sample in &samples {
+                storage
+                    .write_float(*sample)
+                    .expect("Microphone samples should fit in a ByteArray");
+            }
+            storage.set_position(0);
+            let data = ByteArrayObject::from_storage(&mut activation, storage)
+                .expect("ByteArray should be constructible");
+
+            let sample_data_event = activation.avm2().classes().sampledataevent;
+            let event = EventObject::from_class_and_args(
+                &mut activation,
+                sample_data_event,
+                &[
+                    istr!("sampleData").into(),
+                    false.into(),
+                    false.into(),
+                    position.into(),
+                    data.into(),
+                ],
+            );
+            Avm2::dispatch_event(activation.context, event, object);
+        }
+    }
+}
+
+/// The loudness of captured audio, from 0 to 100, as reported by `Microphone.activityLevel`.
+///
+/// This is the root mean square of the samples, which is 100 for a full-scale square wave.
+pub fn activity_level(samples: &[f32]) -> f64 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    let sum: f64 = samples.iter().map(|s| f64::from(*s) * f64::from(*s)).sum();
+    ((sum / samples.len() as f64).sqrt() * 100.0).min(100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{activity_level, Microphones};
+    use crate::avm2::object::{ArrayObject, TObject};
+    use crate::avm2::test_utils::with_avm2;
+    use crate::avm2::{ArrayStorage, Value};
+    use crate::backend::camera::CameraPermission;
+    use crate::backend::microphone::{
+        MicrophoneBackend, MicrophoneDevice, MicrophoneError, MicrophoneHandle,
+    };
+    use crate::player::PlayerBuilder;
+    use crate::string::AvmString;
+    use ruffle_macros::istr;
+    use slotmap::SlotMap;
+    use std::cell::{Cell, RefCell};
+    use std::collections::VecDeque;
+    use std::rc::Rc;
+
+    /// The id that `ScriptedMicrophone` gives its device, which isn't its index.
+    const DEVICE_ID: u32 = 7;
+
+    /// A microphone that plays back a fixed series of captures, one per frame.
+    struct ScriptedMicrophone {
+        handles: SlotMap<MicrophoneHandle, ()>,
+        captures: VecDeque<Vec<f32>>,
+
+        /// The number of times the devices were listed so far.
+        enumerations: Rc<Cell<usize>>,
+
+        /// The ids of the devices that were opened so far.
+        opened: Rc<RefCell<Vec<u32>>>,
+    }
+
+    impl ScriptedMicrophone {
+        fn new(captures: &[&[f32]]) -> Self {
+            Self {
+                handles: SlotMap::with_key(),
+                captures: captures.iter().map(|samples| samples.to_vec()).collect(),
+                enumerations: Rc::new(Cell::new(0)),
+                opened: Rc::new(RefCell::new(Vec::new())),
+            }
+        }
+    }
+
+    impl MicrophoneBackend for ScriptedMicrophone {
+        fn list_devices(&mut self) -> Vec<MicrophoneDevice> {
+            self.enumerations.set(self.enumerations.get() + 1);
+            vec![MicrophoneDevice {
+                id: DEVICE_ID,
+                name: "Scripted Microphone".to_string(),
+            }]
+        }
+
+        fn open(
+            &mut self,
+            id: u32,
+            _sample_rate: u32,
+        ) -> Result<MicrophoneHandle, MicrophoneError> {
+            self.opened.borrow_mut().push(id);
+            Ok(self.handles.insert(()))
+        }
+
+        fn close(&mut self, handle: MicrophoneHandle) {
+            self.handles.remove(handle);
+        }
+
+        fn take_samples(&mut self, handle: MicrophoneHandle) -> Vec<f32> {
+            if !self.handles.contains_key(handle) {
+                return Vec::new();
+            }
+            self.captures.pop_front().unwrap_or_default()
+        }
+    }
+
+    #[test]
+    fn captured_samples_are_dispatched() {
+        let builder = PlayerBuilder::new()
+            .with_microphone(ScriptedMicrophone::new(&[&[0.5, -0.5], &[], &[1.0; 3]]))
+            .with_camera_permission(CameraPermission::Granted);
+        with_avm2(builder, |activation| {
+            let microphone_class: Value = activation.avm2().classes().microphone.into();
+            let get_microphone = AvmString::new_utf8(activation.gc(), "getMicrophone");
+            let microphone =
+                microphone_class.call_public_property(get_microphone, &[], activation)?;
+            let muted = AvmString::new_utf8(activation.gc(), "muted");
+            assert_eq!(
+                microphone.get_public_property(muted, activation)?,
+                false.into()
+            );
+
+            let received: Value =
+                ArrayObject::from_storage(activation, ArrayStorage::new(0)).into();
+            let push = AvmString::new_utf8(activation.gc(), "push");
+            let listener = received.get_public_property(push, activation)?;
+            let add_event_listener = AvmString::new_utf8(activation.gc(), "addEventListener");
+            microphone.call_public_property(
+                add_event_listener,
+                &[istr!("sampleData").into(), listener],
+                activation,
+            )?;
+
+            let activity = AvmString::new_utf8(activation.gc(), "activityLevel");
+            let position = AvmString::new_utf8(activation.gc(), "position");
+            let data = AvmString::new_utf8(activation.gc(), "data");
+            let length = AvmString::new_utf8(activation.gc(), "length");
+            let read_float = AvmString::new_utf8(activation.gc(), "readFloat");
+
+            // The empty capture of the second frame doesn't dispatch an event.
+            for expected_level in [50.0, 50.0, 100.0] {
+                Microphones::update_microphones(activation.context);
+                let level = microphone.get_public_property(activity, activation)?;
+                assert_eq!(level.coerce_to_number(activation)?, expected_level);
+            }
+
+            let events = received.as_object().unwrap();
+            let events = events.as_array_storage().unwrap();
+            let events: Vec<_> = events.iter().map(|event| event.unwrap()).collect();
+            assert_eq!(events.len(), 2);
+            let expected = [(0.0, vec![0.5, -0.5]), (2.0, vec![1.0; 3])];
+            for (event, (expected_position, samples)) in events.into_iter().zip(expected) {
+                let event_position = event.get_public_property(position, activation)?;
+                assert_eq!(
+                    event_position.coerce_to_number(activation)?,
+                    expected_position
+                );
+                let bytes = event.get_public_property(data, activation)?;
+                let bytes_length = bytes.get_public_property(length, activation)?;
+                assert_eq!(
+                    bytes_length.coerce_to_u32(activation)? as usize,
+                    samples.len() * 4
+                );
+                for sample in samples {
+                    let value = bytes.call_public_property(read_float, &[], activation)?;
+                    assert_eq!(value.coerce_to_number(activation)?, f64::from(sample));
+                }
+            }
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn microphone_is_muted_until_permission_is_granted() {
+        let builder = PlayerBuilder::new()
+            .with_microphone(ScriptedMicrophone::new(&[&[1.0]]))
+            .with_camera_permission(CameraPermission::Denied);
+        with_avm2(builder, |activation| {
+            let microphone_class: Value = activation.avm2().classes().microphone.into();
+            let get_microphone = AvmString::new_utf8(activation.gc(), "getMicrophone");
+            let microphone =
+                microphone_class.call_public_property(get_microphone, &[], activation)?;
+            let muted = AvmString::new_utf8(activation.gc(), "muted");
+            assert_eq!(
+                microphone.get_public_property(muted, activation)?,
+                true.into()
+            );
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn devices_are_listed_once_in_quick_succession() {
+        let microphone = ScriptedMicrophone::new(&[]);
+        let enumerations = microphone.enumerations.clone();
+        let opened = microphone.opened.clone();
+        let builder = PlayerBuilder::new()
+            .with_microphone(microphone)
+            .with_camera_permission(CameraPermission::Granted);
+        with_avm2(builder, |activation| {
+            let microphone_class: Value = activation.avm2().classes().microphone.into();
+            let names = AvmString::new_utf8(activation.gc(), "names");
+            let is_supported = AvmString::new_utf8(activation.gc(), "isSupported");
+            let get_microphone = AvmString::new_utf8(activation.gc(), "getMicrophone");
+            let rate = AvmString::new_utf8(activation.gc(), "rate");
+            let set_loop_back = AvmString::new_utf8(activation.gc(), "setLoopBack");
+
+            microphone_class.get_public_property(names, activation)?;
+            microphone_class.get_public_property(is_supported, activation)?;
+            let microphone =
+                microphone_class.call_public_property(get_microphone, &[], activation)?;
+            microphone.set_public_property(rate, 22.into(), activation)?;
+            microphone.call_public_property(set_loop_back, &[], activation)?;
+            assert_eq!(enumerations.get(), 1);
+
+            // The device is opened by its id, not by its index in `names`.
+            assert_eq!(*opened.borrow(), vec![DEVICE_ID]);
+
+            activation.context.microphones.invalidate_devices();
+            microphone_class.get_public_property(names, activation)?;
+            assert_eq!(enumerations.get(), 2);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn activity_level_is_rms() {
+        assert_eq!(activity_level(&[]), 0.0);
+        assert_eq!(activity_level(&[0.0; 8]), 0.0);
+        assert_eq!(activity_level(&[1.0, -1.0, 1.0, -1.0]), 100.0);
+        assert_eq!(activity_level(&[0.5, -0.5]), 50.0);
+
+        // Clipped samples don't go past 100.
+        assert_eq!(activity_level(&[2.0, -2.0]), 100.0);
+    }
+}
@@ -1,7 +1,14 @@
-use ruffle_render::pixel_bender::{PixelBenderType, PixelBenderTypeOpcode};
+use num_traits::FromPrimitive;
+use ruffle_render::pixel_bender::{PixelBenderParam, PixelBenderType, PixelBenderTypeOpcode};
+use smallvec::{smallvec, SmallVec};
 
 use crate::{
-    avm2::{Activation, ArrayObject, ArrayStorage, Error, TObject, Value},
+    avm2::{
+        array_to_f32_buf,
+        error::{argument_error, make_error_2004, type_error, Error2004Type},
+        object::ScriptObject,
+        Activation, ArrayObject, ArrayStorage, Error, Object, TObject, Value,
+    },
     ecma_conversions::f64_to_wrapping_i32,
     string::AvmString,
 };
@@ -15,11 +22,27 @@ pub trait PixelBenderTypeExt {
     where
         Self: Sized;
 
+    /// Converts this value into an array of its components.
+    ///
+    /// With `tint_as_int`, a lone `TInt` is returned as a bare int instead of a
+    /// one-element array. Flash Player only does this for the top-level metadata of
+    /// a `ShaderData`, so that's the only place passing `true`. Parameter values and
+    /// parameter metadata (`ShaderParameter.value`, `minValue`, ...) pass `false`.
+    ///
+    /// Int vectors (`TInt2` to `TInt4`) are arrays either way, since there's no bare
+    /// value they could be unwrapped to.
     fn as_avm2_value<'gc>(
         &self,
         activation: &mut Activation<'_, 'gc>,
         tint_as_int: bool,
     ) -> Result<Value<'gc>, Error<'gc>>;
+
+    /// Like `as_avm2_value`, but `TFloat` and `TInt` are returned as a bare number
+    /// instead of being wrapped in a one-element array.
+    fn as_avm2_value_bare_scalar<'gc>(
+        &self,
+        activation: &mut Activation<'_, 'gc>,
+    ) -> Result<Value<'gc>, Error<'gc>>;
 }
 
 impl PixelBenderTypeExt for PixelBenderType {
@@ -41,82 +64,140 @@ impl PixelBenderTypeExt for PixelBenderType {
                 | PixelBenderTypeOpcode::TFloat3x3
                 | PixelBenderTypeOpcode::TFloat4x4
         );
+        let is_bool = matches!(
+            kind,
+            PixelBenderTypeOpcode::TBool
+                | PixelBenderTypeOpcode::TBool2
+                | PixelBenderTypeOpcode::TBool3
+                | PixelBenderTypeOpcode::TBool4
+        );
 
         match value {
+            // Parameters that were never assigned are read as zero, like in Flash.
+            Value::Undefined => Ok(zero_value(kind)),
+            // A bare number or boolean is a single component, which is coerced to `kind`
+            // like an element of an array. Whole numbers are stored as ints, so the type of
+            // the value itself can't be used.
+            Value::Bool(_) | Value::Number(_) | Value::Integer(_)
+                if component_count(kind).is_some() =>
+            {
+                let expected = component_count(kind).unwrap_or(1);
+                if expected != 1 {
+                    return Err(component_count_error(activation, kind, expected, 1));
+                }
+                Ok(if is_bool {
+                    PixelBenderType::TBool(value.coerce_to_boolean())
+                } else if is_float {
+                    PixelBenderType::TFloat(value.coerce_to_number(activation)? as f32)
+                } else {
+                    PixelBenderType::TInt(saturate_to_i16(f64_to_wrapping_i32(
+                        value.coerce_to_number(activation)?,
+                    )))
+                })
+            }
+            Value::Bool(b) => Ok(PixelBenderType::TBool(b)),
             Value::String(s) => Ok(PixelBenderType::TString(s.to_string())),
             Value::Number(n) => Ok(PixelBenderType::TFloat(n as f32)),
-            Value::Integer(i) => Ok(PixelBenderType::TInt(i as i16)),
+            Value::Integer(i) => Ok(PixelBenderType::TInt(saturate_to_i16(i))),
             Value::Object(o) => {
-                if let Some(array) = o.as_array_storage() {
-                    if is_float {
-                        let mut vals = array.iter().map(|val| {
-                            val.expect("Array with hole")
-                                .coerce_to_number(activation)
-                                .unwrap() as f32
-                        });
-                        match kind {
-                            PixelBenderTypeOpcode::TFloat => {
-                                Ok(PixelBenderType::TFloat(vals.next().unwrap()))
-                            }
-                            PixelBenderTypeOpcode::TFloat2 => Ok(PixelBenderType::TFloat2(
-                                vals.next().unwrap(),
-                                vals.next().unwrap(),
-                            )),
-                            PixelBenderTypeOpcode::TFloat3 => Ok(PixelBenderType::TFloat3(
-                                vals.next().unwrap(),
-                                vals.next().unwrap(),
-                                vals.next().unwrap(),
-                            )),
-                            PixelBenderTypeOpcode::TFloat4 => Ok(PixelBenderType::TFloat4(
-                                vals.next().unwrap(),
-                                vals.next().unwrap(),
-                                vals.next().unwrap(),
-                                vals.next().unwrap(),
-                            )),
-                            PixelBenderTypeOpcode::TFloat2x2 => Ok(PixelBenderType::TFloat2x2(
-                                vals.collect::<Vec<_>>().try_into().unwrap(),
-                            )),
-                            PixelBenderTypeOpcode::TFloat3x3 => Ok(PixelBenderType::TFloat3x3(
-                                vals.collect::<Vec<_>>().try_into().unwrap(),
-                            )),
-                            PixelBenderTypeOpcode::TFloat4x4 => Ok(PixelBenderType::TFloat4x4(
-                                vals.collect::<Vec<_>>().try_into().unwrap(),
-                            )),
-                            _ => unreachable!("Unexpected float kind {kind:?}"),
-                        }
+                // Coercing the elements may call into ActionScript, which could modify the array.
+                // These conversions run for every shader invocation, so values of the largest
+                // type (`TFloat4x4`) are kept on the stack instead of being allocated.
+                let components: SmallVec<[Value<'gc>; 16]> =
+                    if let Some(array) = o.as_array_storage() {
+                        // Like Flash, holes are read as zero rather than as `undefined`,
+                        // which would be NaN.
+                        array
+                            .iter()
+                            .map(|element| element.unwrap_or(Value::Integer(0)))
+                            .collect()
+                    } else if let Some(vector) = o.as_vector_storage() {
+                        vector.iter().collect()
+                    } else if let Some(bytes) = o.as_bytearray() {
+                        // Raw data is read from the current position, using the endianness of
+                        // the ByteArray: 32-bit floats for float types, 32-bit ints otherwise.
+                        let count = component_count(kind).unwrap_or(0);
+                        (0..count)
+                            .map(|_| {
+                                if is_float {
+                                    bytes.read_float().map(Value::from)
+                                } else {
+                                    bytes.read_int().map(Value::from)
+                                }
+                            })
+                            .collect::<Result<_, _>>()
+                            .map_err(|e| e.to_avm(activation))?
                     } else {
-                        let mut vals = array.iter().map(|val| {
-                            val.expect("Array with hole")
-                                .coerce_to_i32(activation)
-                                .unwrap() as i16
-                        });
-                        match kind {
-                            PixelBenderTypeOpcode::TInt => {
-                                Ok(PixelBenderType::TInt(vals.next().unwrap()))
-                            }
-                            PixelBenderTypeOpcode::TInt2 => Ok(PixelBenderType::TInt2(
-                                vals.next().unwrap(),
-                                vals.next().unwrap(),
-                            )),
-                            PixelBenderTypeOpcode::TInt3 => Ok(PixelBenderType::TInt3(
-                                vals.next().unwrap(),
-                                vals.next().unwrap(),
-                                vals.next().unwrap(),
-                            )),
-                            PixelBenderTypeOpcode::TInt4 => Ok(PixelBenderType::TInt4(
-                                vals.next().unwrap(),
-                                vals.next().unwrap(),
-                                vals.next().unwrap(),
-                                vals.next().unwrap(),
-                            )),
-                            _ => unreachable!("Unexpected int kind {kind:?}"),
-                        }
-                    }
-                } else {
-                    panic!("Unexpected object {o:?}")
+                        return Err(unsupported_value_error(activation, value));
+                    };
+
+                tracing::trace!(
+                    target: "ruffle::pixelbender",
+                    "Converting {} components to {kind:?}, starting with {:?}",
+                    components.len(),
+                    &components[..components.len().min(4)],
+                );
+
+                let Some(expected) = component_count(kind) else {
+                    return Err(make_error_2004(activation, Error2004Type::ArgumentError));
+                };
+                if components.len() != expected {
+                    return Err(component_count_error(
+                        activation,
+                        kind,
+                        expected,
+                        components.len(),
+                    ));
                 }
+
+                let result = if is_bool {
+                    let bools: SmallVec<[bool; 4]> = components
+                        .iter()
+                        .map(|val| val.coerce_to_boolean())
+                        .collect();
+                    bools_to_type(kind, &bools)
+                } else if *kind == PixelBenderTypeOpcode::TFloat2x2 {
+                    Some(PixelBenderType::TFloat2x2(array_to_f32_buf(
+                        activation,
+                        &components,
+                    )?))
+                } else if *kind == PixelBenderTypeOpcode::TFloat3x3 {
+                    Some(PixelBenderType::TFloat3x3(array_to_f32_buf(
+                        activation,
+                        &components,
+                    )?))
+                } else if *kind == PixelBenderTypeOpcode::TFloat4x4 {
+                    Some(PixelBenderType::TFloat4x4(array_to_f32_buf(
+                        activation,
+                        &components,
+                    )?))
+                } else if is_float {
+                    let floats = if let Some(numbers) = plain_numbers(&components) {
+                        numbers.iter().map(|n| *n as f32).collect()
+                    } else {
+                        components
+                            .iter()
+                            .map(|val| val.coerce_to_number(activation).map(|n| n as f32))
+                            .collect::<Result<SmallVec<[f32; 16]>, _>>()?
+                    };
+                    floats_to_type(kind, &floats)
+                } else {
+                    let ints = if let Some(numbers) = plain_numbers(&components) {
+                        numbers
+                            .iter()
+                            .map(|n| saturate_to_i16(f64_to_wrapping_i32(*n)))
+                            .collect()
+                    } else {
+                        components
+                            .iter()
+                            .map(|val| val.coerce_to_i32(activation).map(saturate_to_i16))
+                            .collect::<Result<SmallVec<[i16; 4]>, _>>()?
+                    };
+                    ints_to_type(kind, &ints)
+                };
+                result.ok_or_else(|| make_error_2004(activation, Error2004Type::ArgumentError))
             }
-            _ => panic!("Unexpected value {value:?}"),
+            _ => Err(unsupported_value_error(activation, value)),
         }
     }
     fn as_avm2_value<'gc>(
@@ -124,15 +205,10 @@ impl PixelBenderTypeExt for PixelBenderType {
         activation: &mut Activation<'_, 'gc>,
         tint_as_int: bool,
     ) -> Result<Value<'gc>, Error<'gc>> {
-        // Flash appears to use a uint/int if the float has no fractional part
-        let cv = |f: &f32| -> Value<'gc> {
-            if f.fract() == 0.0 {
-                f64_to_wrapping_i32(*f as f64).into()
-            } else {
-                (*f).into()
-            }
-        };
-        let vals: Vec<Value<'gc>> = match self {
+        let cv = |f: &f32| float_to_avm2(*f);
+        // Like in `from_avm2_value`, the components are gathered on the stack, so that the
+        // only allocation is the one made by the array itself.
+        let vals: SmallVec<[Value<'gc>; 16]> = match self {
             PixelBenderType::TString(string) => {
                 return Ok(AvmString::new_utf8(activation.gc(), string).into());
             }
@@ -140,23 +216,1118 @@ impl PixelBenderTypeExt for PixelBenderType {
                 if tint_as_int {
                     return Ok((*i).into());
                 } else {
-                    vec![(*i).into()]
+                    smallvec![(*i).into()]
                 }
             }
-            PixelBenderType::TFloat(f) => vec![cv(f)],
-            PixelBenderType::TFloat2(f1, f2) => vec![cv(f1), cv(f2)],
-            PixelBenderType::TFloat3(f1, f2, f3) => vec![cv(f1), cv(f2), cv(f3)],
-            PixelBenderType::TFloat4(f1, f2, f3, f4) => vec![cv(f1), cv(f2), cv(f3), cv(f4)],
+            PixelBenderType::TFloat(f) => smallvec![cv(f)],
+            PixelBenderType::TFloat2(f1, f2) => smallvec![cv(f1), cv(f2)],
+            PixelBenderType::TFloat3(f1, f2, f3) => smallvec![cv(f1), cv(f2), cv(f3)],
+            PixelBenderType::TFloat4(f1, f2, f3, f4) => smallvec![cv(f1), cv(f2), cv(f3), cv(f4)],
             PixelBenderType::TFloat2x2(floats) => floats.iter().map(cv).collect(),
             PixelBenderType::TFloat3x3(floats) => floats.iter().map(cv).collect(),
             PixelBenderType::TFloat4x4(floats) => floats.iter().map(cv).collect(),
-            PixelBenderType::TInt2(i1, i2) => vec![(*i1).into(), (*i2).into()],
-            PixelBenderType::TInt3(i1, i2, i3) => vec![(*i1).into(), (*i2).into(), (*i3).into()],
+            PixelBenderType::TInt2(i1, i2) => smallvec![(*i1).into(), (*i2).into()],
+            PixelBenderType::TInt3(i1, i2, i3) => {
+                smallvec![(*i1).into(), (*i2).into(), (*i3).into()]
+            }
             PixelBenderType::TInt4(i1, i2, i3, i4) => {
-                vec![(*i1).into(), (*i2).into(), (*i3).into(), (*i4).into()]
+                smallvec![(*i1).into(), (*i2).into(), (*i3).into(), (*i4).into()]
+            }
+            PixelBenderType::TBool(b1) => smallvec![(*b1).into()],
+            PixelBenderType::TBool2(b1, b2) => smallvec![(*b1).into(), (*b2).into()],
+            PixelBenderType::TBool3(b1, b2, b3) => {
+                smallvec![(*b1).into(), (*b2).into(), (*b3).into()]
+            }
+            PixelBenderType::TBool4(b1, b2, b3, b4) => {
+                smallvec![(*b1).into(), (*b2).into(), (*b3).into(), (*b4).into()]
             }
         };
+        tracing::trace!(
+            target: "ruffle::pixelbender",
+            "Converted {self:?} to {} components, starting with {:?}",
+            vals.len(),
+            &vals[..vals.len().min(4)],
+        );
         let storage = ArrayStorage::from_args(&vals);
         Ok(ArrayObject::from_storage(activation, storage).into())
     }
+
+    fn as_avm2_value_bare_scalar<'gc>(
+        &self,
+        activation: &mut Activation<'_, 'gc>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        match self {
+            PixelBenderType::TFloat(f) => Ok(float_to_avm2(*f)),
+            PixelBenderType::TInt(i) => Ok((*i).into()),
+            PixelBenderType::TBool(b) => Ok((*b).into()),
+            _ => self.as_avm2_value(activation, true),
+        }
+    }
+}
+
+/// Describes a shader value for the debug UI, e.g. `TFloat3x3[1,0,0, 0,1,0, 0,0,1]`.
+///
+/// The components are listed in the same order as `as_avm2_value` returns them, with
+/// the rows of matrices separated by spaces.
+pub fn describe_value(value: &PixelBenderType) -> String {
+    fn join<T: std::fmt::Display>(components: &[T], row_length: usize) -> String {
+        components
+            .chunks(row_length)
+            .map(|row| {
+                row.iter()
+                    .map(|component| component.to_string())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    let (name, components) = match value {
+        PixelBenderType::TString(string) => ("TString", format!("{string:?}")),
+        PixelBenderType::TInt(i) => ("TInt", join(&[*i], 1)),
+        PixelBenderType::TFloat(f) => ("TFloat", join(&[*f], 1)),
+        PixelBenderType::TFloat2(f1, f2) => ("TFloat2", join(&[*f1, *f2], 2)),
+        PixelBenderType::TFloat3(f1, f2, f3) => ("TFloat3", join(&[*f1, *f2, *f3], 3)),
+        PixelBenderType::TFloat4(f1, f2, f3, f4) => ("TFloat4", join(&[*f1, *f2, *f3, *f4], 4)),
+        PixelBenderType::TFloat2x2(floats) => ("TFloat2x2", join(floats, 2)),
+        PixelBenderType::TFloat3x3(floats) => ("TFloat3x3", join(floats, 3)),
+        PixelBenderType::TFloat4x4(floats) => ("TFloat4x4", join(floats, 4)),
+        PixelBenderType::TInt2(i1, i2) => ("TInt2", join(&[*i1, *i2], 2)),
+        PixelBenderType::TInt3(i1, i2, i3) => ("TInt3", join(&[*i1, *i2, *i3], 3)),
+        PixelBenderType::TInt4(i1, i2, i3, i4) => ("TInt4", join(&[*i1, *i2, *i3, *i4], 4)),
+        PixelBenderType::TBool(b1) => ("TBool", join(&[*b1], 1)),
+        PixelBenderType::TBool2(b1, b2) => ("TBool2", join(&[*b1, *b2], 2)),
+        PixelBenderType::TBool3(b1, b2, b3) => ("TBool3", join(&[*b1, *b2, *b3], 3)),
+        PixelBenderType::TBool4(b1, b2, b3, b4) => ("TBool4", join(&[*b1, *b2, *b3, *b4], 4)),
+    };
+    format!("{name}[{components}]")
+}
+
+/// The value of a parameter of the given type that was never assigned.
+fn zero_value(kind: &PixelBenderTypeOpcode) -> PixelBenderType {
+    match kind {
+        PixelBenderTypeOpcode::TFloat => PixelBenderType::TFloat(0.0),
+        PixelBenderTypeOpcode::TFloat2 => PixelBenderType::TFloat2(0.0, 0.0),
+        PixelBenderTypeOpcode::TFloat3 => PixelBenderType::TFloat3(0.0, 0.0, 0.0),
+        PixelBenderTypeOpcode::TFloat4 => PixelBenderType::TFloat4(0.0, 0.0, 0.0, 0.0),
+        PixelBenderTypeOpcode::TFloat2x2 => PixelBenderType::TFloat2x2([0.0; 4]),
+        PixelBenderTypeOpcode::TFloat3x3 => PixelBenderType::TFloat3x3([0.0; 9]),
+        PixelBenderTypeOpcode::TFloat4x4 => PixelBenderType::TFloat4x4([0.0; 16]),
+        PixelBenderTypeOpcode::TInt => PixelBenderType::TInt(0),
+        PixelBenderTypeOpcode::TInt2 => PixelBenderType::TInt2(0, 0),
+        PixelBenderTypeOpcode::TInt3 => PixelBenderType::TInt3(0, 0, 0),
+        PixelBenderTypeOpcode::TInt4 => PixelBenderType::TInt4(0, 0, 0, 0),
+        PixelBenderTypeOpcode::TString => PixelBenderType::TString(String::new()),
+        PixelBenderTypeOpcode::TBool => PixelBenderType::TBool(false),
+        PixelBenderTypeOpcode::TBool2 => PixelBenderType::TBool2(false, false),
+        PixelBenderTypeOpcode::TBool3 => PixelBenderType::TBool3(false, false, false),
+        PixelBenderTypeOpcode::TBool4 => PixelBenderType::TBool4(false, false, false, false),
+    }
+}
+
+/// The error thrown when a value that isn't a number, string or array, such as a
+/// function or an XML object, is converted into a shader value.
+fn unsupported_value_error<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    value: Value<'gc>,
+) -> Error<'gc> {
+    let kind = match value {
+        Value::Undefined => "undefined".into(),
+        Value::Null => "null".into(),
+        _ => value.instance_of_class_name(activation).to_string(),
+    };
+    match type_error(
+        activation,
+        &format!("Error #1034: Type Coercion failed: cannot convert {kind} to Array."),
+        1034,
+    ) {
+        Ok(error) => Error::AvmError(error),
+        Err(e) => e,
+    }
+}
+
+/// The number of components that an array needs to have to be converted to `kind`,
+/// or `None` if `kind` can't be given as an array.
+fn component_count(kind: &PixelBenderTypeOpcode) -> Option<usize> {
+    match kind {
+        PixelBenderTypeOpcode::TFloat
+        | PixelBenderTypeOpcode::TInt
+        | PixelBenderTypeOpcode::TBool => Some(1),
+        PixelBenderTypeOpcode::TFloat2
+        | PixelBenderTypeOpcode::TInt2
+        | PixelBenderTypeOpcode::TBool2 => Some(2),
+        PixelBenderTypeOpcode::TFloat3
+        | PixelBenderTypeOpcode::TInt3
+        | PixelBenderTypeOpcode::TBool3 => Some(3),
+        PixelBenderTypeOpcode::TFloat4
+        | PixelBenderTypeOpcode::TInt4
+        | PixelBenderTypeOpcode::TBool4 => Some(4),
+        PixelBenderTypeOpcode::TFloat2x2 => Some(4),
+        PixelBenderTypeOpcode::TFloat3x3 => Some(9),
+        PixelBenderTypeOpcode::TFloat4x4 => Some(16),
+        PixelBenderTypeOpcode::TString => None,
+    }
+}
+
+/// Reads the components directly if they're all numbers already, which is the case for
+/// array literals. Any other value has to go through the (much slower) coercion.
+fn plain_numbers(components: &[Value<'_>]) -> Option<SmallVec<[f64; 16]>> {
+    components
+        .iter()
+        .map(|value| match value {
+            Value::Number(n) => Some(*n),
+            Value::Integer(i) => Some(*i as f64),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The error thrown when an array has more or fewer components than its type needs.
+fn component_count_error<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    kind: &PixelBenderTypeOpcode,
+    expected: usize,
+    actual: usize,
+) -> Error<'gc> {
+    match argument_error(
+        activation,
+        &format!(
+            "Error #2004: One of the parameters is invalid. A {kind} value needs {expected} components, but the array has {actual}."
+        ),
+        2004,
+    ) {
+        Ok(error) => Error::AvmError(error),
+        Err(e) => e,
+    }
+}
+
+/// Builds a float vector type from the components of an array, or `None` if they don't
+/// fit `kind`. Matrices are converted with `array_to_f32_buf` instead.
+fn floats_to_type(kind: &PixelBenderTypeOpcode, floats: &[f32]) -> Option<PixelBenderType> {
+    Some(match (kind, floats) {
+        (PixelBenderTypeOpcode::TFloat, [x]) => PixelBenderType::TFloat(*x),
+        (PixelBenderTypeOpcode::TFloat2, [x, y]) => PixelBenderType::TFloat2(*x, *y),
+        (PixelBenderTypeOpcode::TFloat3, [x, y, z]) => PixelBenderType::TFloat3(*x, *y, *z),
+        (PixelBenderTypeOpcode::TFloat4, [x, y, z, w]) => PixelBenderType::TFloat4(*x, *y, *z, *w),
+        _ => return None,
+    })
+}
+
+/// Builds an int type from the components of an array, or `None` if they don't fit `kind`.
+fn ints_to_type(kind: &PixelBenderTypeOpcode, ints: &[i16]) -> Option<PixelBenderType> {
+    Some(match (kind, ints) {
+        (PixelBenderTypeOpcode::TInt, [x]) => PixelBenderType::TInt(*x),
+        (PixelBenderTypeOpcode::TInt2, [x, y]) => PixelBenderType::TInt2(*x, *y),
+        (PixelBenderTypeOpcode::TInt3, [x, y, z]) => PixelBenderType::TInt3(*x, *y, *z),
+        (PixelBenderTypeOpcode::TInt4, [x, y, z, w]) => PixelBenderType::TInt4(*x, *y, *z, *w),
+        _ => return None,
+    })
+}
+
+/// Builds a bool type from the components of an array, or `None` if they don't fit `kind`.
+fn bools_to_type(kind: &PixelBenderTypeOpcode, bools: &[bool]) -> Option<PixelBenderType> {
+    Some(match (kind, bools) {
+        (PixelBenderTypeOpcode::TBool, [x]) => PixelBenderType::TBool(*x),
+        (PixelBenderTypeOpcode::TBool2, [x, y]) => PixelBenderType::TBool2(*x, *y),
+        (PixelBenderTypeOpcode::TBool3, [x, y, z]) => PixelBenderType::TBool3(*x, *y, *z),
+        (PixelBenderTypeOpcode::TBool4, [x, y, z, w]) => PixelBenderType::TBool4(*x, *y, *z, *w),
+        _ => return None,
+    })
+}
+
+/// Looks up a type by the name that `ShaderParameter.type` reports for it.
+pub fn type_opcode_from_avm2_name(name: &str) -> Option<PixelBenderTypeOpcode> {
+    // ActionScript calls the matrix types `matrixNxN` instead of `floatNxN`.
+    match name {
+        "matrix2x2" => Some(PixelBenderTypeOpcode::TFloat2x2),
+        "matrix3x3" => Some(PixelBenderTypeOpcode::TFloat3x3),
+        "matrix4x4" => Some(PixelBenderTypeOpcode::TFloat4x4),
+        _ => PixelBenderTypeOpcode::from_name(name).ok(),
+    }
+}
+
+/// Converts the default values of all of a shader's parameters into a dynamic object,
+/// keyed by parameter name. Parameters without a `defaultValue` are left out.
+pub fn parameter_values_as_avm2_object<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    params: &[PixelBenderParam],
+) -> Result<Object<'gc>, Error<'gc>> {
+    let object = ScriptObject::new_object(activation);
+    for param in params {
+        let PixelBenderParam::Normal { name, metadata, .. } = param else {
+            continue;
+        };
+        let Some(default) = metadata.iter().find(|meta| meta.key == "defaultValue") else {
+            continue;
+        };
+        let value = default.value.as_avm2_value(activation, false)?;
+        let name = AvmString::new_utf8(activation.gc(), name);
+        object.set_string_property_local(name, value, activation)?;
+    }
+    Ok(object)
+}
+
+// Flash appears to use a uint/int if the float has no fractional part.
+// NaN, infinities and floats too large for an int stay Numbers, rather than wrapping around.
+fn float_to_avm2<'gc>(f: f32) -> Value<'gc> {
+    let f = f64::from(f);
+    if f.is_finite() && f.fract() == 0.0 && (i32::MIN.into()..=i32::MAX.into()).contains(&f) {
+        f64_to_wrapping_i32(f).into()
+    } else {
+        f.into()
+    }
+}
+
+/// Pixel Bender ints are only 16 bits wide. Values outside of that range (such as
+/// coordinates past the edge of a large image) saturate, rather than wrapping
+/// around and flipping their sign.
+fn saturate_to_i16(value: i32) -> i16 {
+    value.clamp(i16::MIN.into(), i16::MAX.into()) as i16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::avm2::bytearray::{ByteArrayStorage, Endian};
+    use crate::avm2::globals::flash::display::shader_parameter::make_shader_parameter;
+    use crate::avm2::object::{ByteArrayObject, VectorObject};
+    use crate::avm2::test_utils::with_avm2;
+    use crate::avm2::vector::VectorStorage;
+    use crate::player::PlayerBuilder;
+    use ruffle_render::pixel_bender::{
+        PixelBenderMetadata, PixelBenderParamQualifier, PixelBenderReg, PixelBenderRegChannel,
+        PixelBenderRegKind,
+    };
+
+    fn normal_param(
+        name: &str,
+        param_type: PixelBenderTypeOpcode,
+        default: Option<PixelBenderType>,
+    ) -> PixelBenderParam {
+        PixelBenderParam::Normal {
+            qualifier: PixelBenderParamQualifier::Input,
+            param_type,
+            reg: PixelBenderReg {
+                index: 0,
+                channels: PixelBenderRegChannel::RGBA.to_vec(),
+                kind: PixelBenderRegKind::Float,
+            },
+            name: name.to_string(),
+            metadata: default
+                .into_iter()
+                .map(|value| PixelBenderMetadata {
+                    key: "defaultValue".to_string(),
+                    value,
+                })
+                .collect(),
+        }
+    }
+
+    fn array_values<'gc>(value: Value<'gc>) -> Vec<Value<'gc>> {
+        let array = value.as_object().unwrap();
+        let array = array.as_array_storage().unwrap();
+        let values = array.iter().map(|val| val.unwrap()).collect();
+        values
+    }
+
+    #[test]
+    fn tint2_keeps_sign_when_converted_to_avm2() {
+        with_avm2(PlayerBuilder::new(), |activation| {
+            for (x, y) in [(-1, -2), (-300, 4000), (i16::MIN, i16::MAX)] {
+                let value = PixelBenderType::TInt2(x, y).as_avm2_value(activation, true)?;
+                assert_eq!(
+                    array_values(value),
+                    vec![Value::Integer(x.into()), Value::Integer(y.into())]
+                );
+            }
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn tint2_from_avm2_array() {
+        with_avm2(PlayerBuilder::new(), |activation| {
+            let cases = [
+                ((-1, -2), (-1, -2)),
+                ((-300, 4000), (-300, 4000)),
+                ((40000, -40000), (i16::MAX, i16::MIN)),
+                ((70000, 65537), (i16::MAX, i16::MAX)),
+            ];
+            for ((x, y), expected) in cases {
+                let storage = ArrayStorage::from_args(&[x.into(), y.into()]);
+                let array = ArrayObject::from_storage(activation, storage);
+                let value = PixelBenderType::from_avm2_value(
+                    activation,
+                    array.into(),
+                    &PixelBenderTypeOpcode::TInt2,
+                )?;
+                assert_eq!(value, PixelBenderType::TInt2(expected.0, expected.1));
+            }
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn fractional_ints_round_toward_zero() {
+        with_avm2(PlayerBuilder::new(), |activation| {
+            // Like `coerce_to_i32`, which wraps to 32 bits before saturating to 16 bits.
+            let cases = [
+                ((1.9, -2.9), (1, -2)),
+                ((0.5, -0.5), (0, 0)),
+                ((40000.7, -40000.7), (i16::MAX, i16::MIN)),
+                ((4294967297.5, -4294967297.5), (1, -1)),
+            ];
+            for ((x, y), expected) in cases {
+                let storage = ArrayStorage::from_args(&[x.into(), y.into()]);
+                let array = ArrayObject::from_storage(activation, storage);
+                let value = PixelBenderType::from_avm2_value(
+                    activation,
+                    array.into(),
+                    &PixelBenderTypeOpcode::TInt2,
+                )?;
+                assert_eq!(value, PixelBenderType::TInt2(expected.0, expected.1));
+            }
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn tint2_round_trip() {
+        with_avm2(PlayerBuilder::new(), |activation| {
+            let original = PixelBenderType::TInt2(-128, 1024);
+            let value = original.as_avm2_value(activation, true)?;
+            let converted =
+                PixelBenderType::from_avm2_value(activation, value, &PixelBenderTypeOpcode::TInt2)?;
+            assert_eq!(converted, original);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn every_type_round_trips() {
+        let values = [
+            PixelBenderType::TFloat(1.5),
+            PixelBenderType::TFloat2(1.5, -2.0),
+            PixelBenderType::TFloat3(0.25, 3.0, -0.5),
+            PixelBenderType::TFloat4(1.0, 2.5, 3.0, 4.5),
+            PixelBenderType::TFloat2x2([1.0, 0.5, -0.5, 1.0]),
+            PixelBenderType::TFloat3x3(std::array::from_fn(|i| i as f32 * 0.5)),
+            PixelBenderType::TFloat4x4(std::array::from_fn(|i| i as f32 - 7.5)),
+            PixelBenderType::TInt(-7),
+            PixelBenderType::TInt2(-1, 2),
+            PixelBenderType::TInt3(i16::MIN, 0, i16::MAX),
+            PixelBenderType::TInt4(1, 2, 3, 4),
+            PixelBenderType::TString("Adobe examples".to_string()),
+            PixelBenderType::TBool(true),
+            PixelBenderType::TBool2(false, true),
+            PixelBenderType::TBool3(true, true, false),
+            PixelBenderType::TBool4(false, false, true, true),
+        ];
+        let opcodes: Vec<_> = (0..=u8::MAX)
+            .filter_map(PixelBenderTypeOpcode::from_u8)
+            .collect();
+        assert_eq!(opcodes.len(), values.len(), "Every type should be covered");
+
+        with_avm2(PlayerBuilder::new(), |activation| {
+            for (original, kind) in values.iter().zip(&opcodes) {
+                for tint_as_int in [false, true] {
+                    let value = original.as_avm2_value(activation, tint_as_int)?;
+                    let converted = PixelBenderType::from_avm2_value(activation, value, kind)?;
+                    assert_eq!(&converted, original, "{kind:?}, tint_as_int: {tint_as_int}");
+                }
+            }
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn whole_floats_become_ints() {
+        with_avm2(PlayerBuilder::new(), |activation| {
+            let value =
+                PixelBenderType::TFloat3(2.0, 2.5, -3.0).as_avm2_value(activation, false)?;
+            assert_eq!(
+                array_values(value),
+                vec![Value::Integer(2), Value::Number(2.5), Value::Integer(-3)]
+            );
+
+            let value = PixelBenderType::TFloat2x2([0.0, -0.5, 1.0, 16.0])
+                .as_avm2_value(activation, false)?;
+            assert_eq!(
+                array_values(value),
+                vec![
+                    Value::Integer(0),
+                    Value::Number(-0.5),
+                    Value::Integer(1),
+                    Value::Integer(16)
+                ]
+            );
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn non_finite_floats_stay_numbers() {
+        with_avm2(PlayerBuilder::new(), |activation| {
+            let value = PixelBenderType::TFloat4(f32::NAN, f32::INFINITY, f32::NEG_INFINITY, 1e10)
+                .as_avm2_value(activation, false)?;
+            let values = array_values(value);
+            assert!(matches!(values[0], Value::Number(n) if n.is_nan()));
+            assert_eq!(values[1], Value::Number(f64::INFINITY));
+            assert_eq!(values[2], Value::Number(f64::NEG_INFINITY));
+            assert_eq!(values[3], Value::Number(1e10));
+
+            let value =
+                PixelBenderType::TFloat(-2147483648.0).as_avm2_value_bare_scalar(activation)?;
+            assert_eq!(value, Value::Integer(i32::MIN));
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn scalars_in_array_mode() {
+        with_avm2(PlayerBuilder::new(), |activation| {
+            let value = PixelBenderType::TFloat(1.5).as_avm2_value(activation, false)?;
+            assert_eq!(array_values(value), vec![Value::Number(1.5)]);
+
+            let value = PixelBenderType::TInt(-7).as_avm2_value(activation, false)?;
+            assert_eq!(array_values(value), vec![Value::Integer(-7)]);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn float_scalars_and_single_element_arrays_agree() {
+        with_avm2(PlayerBuilder::new(), |activation| {
+            let kind = PixelBenderTypeOpcode::TFloat;
+            for (scalar, expected) in [(Value::Number(0.5), 0.5), (Value::Integer(2), 2.0)] {
+                let array =
+                    ArrayObject::from_storage(activation, ArrayStorage::from_args(&[scalar]));
+                assert_eq!(
+                    PixelBenderType::from_avm2_value(activation, scalar, &kind)?,
+                    PixelBenderType::TFloat(expected)
+                );
+                assert_eq!(
+                    PixelBenderType::from_avm2_value(activation, array.into(), &kind)?,
+                    PixelBenderType::TFloat(expected)
+                );
+            }
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn mixed_components_are_coerced() {
+        with_avm2(PlayerBuilder::new(), |activation| {
+            let half = AvmString::new_utf8(activation.gc(), "0.5");
+            let array = ArrayObject::from_storage(
+                activation,
+                ArrayStorage::from_args(&[Value::Integer(1), half.into()]),
+            );
+            assert_eq!(
+                PixelBenderType::from_avm2_value(
+                    activation,
+                    array.into(),
+                    &PixelBenderTypeOpcode::TFloat2
+                )?,
+                PixelBenderType::TFloat2(1.0, 0.5)
+            );
+
+            let array = ArrayObject::from_storage(
+                activation,
+                ArrayStorage::from_args(&[Value::Number(2.9), true.into()]),
+            );
+            assert_eq!(
+                PixelBenderType::from_avm2_value(
+                    activation,
+                    array.into(),
+                    &PixelBenderTypeOpcode::TInt2
+                )?,
+                PixelBenderType::TInt2(2, 1)
+            );
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn scalars_in_bare_mode() {
+        with_avm2(PlayerBuilder::new(), |activation| {
+            let value = PixelBenderType::TFloat(1.5).as_avm2_value_bare_scalar(activation)?;
+            assert_eq!(value, Value::Number(1.5));
+
+            let value = PixelBenderType::TFloat(3.0).as_avm2_value_bare_scalar(activation)?;
+            assert_eq!(value, Value::Integer(3));
+
+            let value = PixelBenderType::TInt(-7).as_avm2_value_bare_scalar(activation)?;
+            assert_eq!(value, Value::Integer(-7));
+
+            // Vectors are still returned as arrays.
+            let value = PixelBenderType::TFloat2(1.5, 2.0).as_avm2_value_bare_scalar(activation)?;
+            assert_eq!(
+                array_values(value),
+                vec![Value::Number(1.5), Value::Integer(2)]
+            );
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn parameter_values_keyed_by_name() {
+        let params = [
+            normal_param(
+                "radius",
+                PixelBenderTypeOpcode::TFloat,
+                Some(PixelBenderType::TFloat(2.5)),
+            ),
+            normal_param(
+                "center",
+                PixelBenderTypeOpcode::TInt2,
+                Some(PixelBenderType::TInt2(-10, 20)),
+            ),
+            normal_param("amount", PixelBenderTypeOpcode::TFloat, None),
+            normal_param("toString", PixelBenderTypeOpcode::TFloat, None),
+            PixelBenderParam::Texture {
+                index: 0,
+                channels: 4,
+                name: "src".to_string(),
+            },
+        ];
+        with_avm2(PlayerBuilder::new(), |activation| {
+            let object = parameter_values_as_avm2_object(activation, &params)?;
+
+            let radius = AvmString::new_utf8(activation.gc(), "radius");
+            let radius = object.get_string_property_local(radius, activation)?;
+            assert_eq!(array_values(radius), vec![Value::Number(2.5)]);
+
+            let center = AvmString::new_utf8(activation.gc(), "center");
+            let center = object.get_string_property_local(center, activation)?;
+            assert_eq!(
+                array_values(center),
+                vec![Value::Integer(-10), Value::Integer(20)]
+            );
+
+            for missing in ["amount", "toString", "src"] {
+                let missing = AvmString::new_utf8(activation.gc(), missing);
+                assert!(!object.has_own_property_string(missing, activation)?);
+            }
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn assigned_value_is_clamped_to_declared_range() {
+        let mut param = normal_param(
+            "offset",
+            PixelBenderTypeOpcode::TFloat2,
+            Some(PixelBenderType::TFloat2(0.0, 0.0)),
+        );
+        let PixelBenderParam::Normal { metadata, .. } = &mut param else {
+            unreachable!();
+        };
+        metadata.push(PixelBenderMetadata {
+            key: "minValue".to_string(),
+            value: PixelBenderType::TFloat2(-10.0, 0.0),
+        });
+        metadata.push(PixelBenderMetadata {
+            key: "maxValue".to_string(),
+            value: PixelBenderType::TFloat2(10.0, 1.0),
+        });
+
+        with_avm2(PlayerBuilder::new(), |activation| {
+            let parameter = make_shader_parameter(activation, &param, 0, Value::Undefined)?;
+            let value_name = AvmString::new_utf8(activation.gc(), "value");
+
+            let value: ArrayStorage = [Value::Number(25.0), Value::Number(-3.5)]
+                .into_iter()
+                .collect();
+            let value = ArrayObject::from_storage(activation, value);
+            parameter.set_public_property(value_name, value.into(), activation)?;
+            let stored = parameter.get_public_property(value_name, activation)?;
+            assert_eq!(
+                array_values(stored),
+                vec![Value::Number(10.0), Value::Number(0.0)]
+            );
+
+            let value: ArrayStorage = [Value::Number(-2.5), Value::Number(0.5)]
+                .into_iter()
+                .collect();
+            let value = ArrayObject::from_storage(activation, value);
+            parameter.set_public_property(value_name, value.into(), activation)?;
+            let stored = parameter.get_public_property(value_name, activation)?;
+            assert_eq!(
+                array_values(stored),
+                vec![Value::Number(-2.5), Value::Number(0.5)]
+            );
+
+            // Non-numeric components would become NaN, so the value is left as it is.
+            let abc = AvmString::new_utf8(activation.gc(), "abc");
+            let value: ArrayStorage = [abc.into(), Value::Number(5.0)].into_iter().collect();
+            let value = ArrayObject::from_storage(activation, value);
+            parameter.set_public_property(value_name, value.into(), activation)?;
+            let stored = parameter.get_public_property(value_name, activation)?;
+            assert_eq!(array_values(stored), vec![abc.into(), Value::Number(5.0)]);
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn tint_scalar_depends_on_tint_as_int() {
+        with_avm2(PlayerBuilder::new(), |activation| {
+            let value = PixelBenderType::TInt(5).as_avm2_value(activation, false)?;
+            assert_eq!(array_values(value), vec![Value::Integer(5)]);
+
+            let value = PixelBenderType::TInt(5).as_avm2_value(activation, true)?;
+            assert_eq!(value, Value::Integer(5));
+
+            // Only lone ints are affected, int vectors stay arrays.
+            let vectors = [
+                PixelBenderType::TInt2(5, 6),
+                PixelBenderType::TInt3(5, 6, 7),
+                PixelBenderType::TInt4(5, 6, 7, 8),
+            ];
+            for vector in vectors {
+                let expected = vector.as_avm2_value(activation, false)?;
+                let value = vector.as_avm2_value(activation, true)?;
+                assert_eq!(array_values(value), array_values(expected));
+                assert_eq!(array_values(value).len(), vector.len());
+            }
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn undefined_is_zero() {
+        let expected = [
+            PixelBenderType::TFloat(0.0),
+            PixelBenderType::TFloat2(0.0, 0.0),
+            PixelBenderType::TFloat3(0.0, 0.0, 0.0),
+            PixelBenderType::TFloat4(0.0, 0.0, 0.0, 0.0),
+            PixelBenderType::TFloat2x2([0.0; 4]),
+            PixelBenderType::TFloat3x3([0.0; 9]),
+            PixelBenderType::TFloat4x4([0.0; 16]),
+            PixelBenderType::TInt(0),
+            PixelBenderType::TInt2(0, 0),
+            PixelBenderType::TInt3(0, 0, 0),
+            PixelBenderType::TInt4(0, 0, 0, 0),
+            PixelBenderType::TString(String::new()),
+            PixelBenderType::TBool(false),
+            PixelBenderType::TBool2(false, false),
+            PixelBenderType::TBool3(false, false, false),
+            PixelBenderType::TBool4(false, false, false, false),
+        ];
+        with_avm2(PlayerBuilder::new(), |activation| {
+            for (opcode, expected) in (0x1..=0x10).zip(expected) {
+                let kind = PixelBenderTypeOpcode::from_u8(opcode).unwrap();
+                assert_eq!(
+                    PixelBenderType::from_avm2_value(activation, Value::Undefined, &kind)?,
+                    expected,
+                    "{kind:?}"
+                );
+            }
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn function_and_xml_values_throw() {
+        let param = normal_param("amount", PixelBenderTypeOpcode::TFloat, None);
+        with_avm2(PlayerBuilder::new(), |activation| {
+            let array: Value = ArrayObject::from_storage(activation, ArrayStorage::new(0)).into();
+            let push = AvmString::new_utf8(activation.gc(), "push");
+            let function = array.get_public_property(push, activation)?;
+
+            let xml_class = activation.avm2().classes().xml;
+            let source = AvmString::new_utf8(activation.gc(), "<a/>");
+            let xml = xml_class.construct(activation, &[source.into()])?;
+
+            let parameter = make_shader_parameter(activation, &param, 0, Value::Undefined)?;
+            let value_name = AvmString::new_utf8(activation.gc(), "value");
+            for value in [function, xml] {
+                let result = PixelBenderType::from_avm2_value(
+                    activation,
+                    value,
+                    &PixelBenderTypeOpcode::TFloat,
+                );
+                assert!(matches!(result, Err(Error::AvmError(_))));
+
+                let result = parameter.set_public_property(value_name, value, activation);
+                assert!(matches!(result, Err(Error::AvmError(_))));
+            }
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn plain_object_for_vector_throws_type_error() {
+        with_avm2(PlayerBuilder::new(), |activation| {
+            let object = ScriptObject::new_object(activation);
+            let result = PixelBenderType::from_avm2_value(
+                activation,
+                object.into(),
+                &PixelBenderTypeOpcode::TFloat4,
+            );
+            let Err(Error::AvmError(error)) = result else {
+                panic!("Expected a TypeError, got {result:?}");
+            };
+            let message = AvmString::new_utf8(activation.gc(), "message");
+            let message = error.get_public_property(message, activation)?;
+            assert_eq!(
+                message.coerce_to_string(activation)?.to_string(),
+                "Error #1034: Type Coercion failed: cannot convert Object to Array."
+            );
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn bools_from_avm2() {
+        with_avm2(PlayerBuilder::new(), |activation| {
+            for (value, expected) in [
+                (Value::Bool(true), true),
+                (Value::Number(0.5), true),
+                (Value::Integer(0), false),
+            ] {
+                let value = PixelBenderType::from_avm2_value(
+                    activation,
+                    value,
+                    &PixelBenderTypeOpcode::TBool,
+                )?;
+                assert_eq!(value, PixelBenderType::TBool(expected));
+            }
+
+            let storage = ArrayStorage::from_args(&[true.into(), 0.into(), 2.into()]);
+            let array = ArrayObject::from_storage(activation, storage);
+            let value = PixelBenderType::from_avm2_value(
+                activation,
+                array.into(),
+                &PixelBenderTypeOpcode::TBool3,
+            )?;
+            assert_eq!(value, PixelBenderType::TBool3(true, false, true));
+
+            let value = value.as_avm2_value(activation, false)?;
+            assert_eq!(
+                array_values(value),
+                vec![Value::Bool(true), Value::Bool(false), Value::Bool(true)]
+            );
+
+            let value = PixelBenderType::TBool(true).as_avm2_value_bare_scalar(activation)?;
+            assert_eq!(value, Value::Bool(true));
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn vectors_are_accepted() {
+        with_avm2(PlayerBuilder::new(), |activation| {
+            let number_class = activation.avm2().class_defs().number;
+            let storage = VectorStorage::from_values(
+                vec![Value::Number(1.5), Value::Number(-2.0)],
+                false,
+                Some(number_class),
+            );
+            let vector: Value = VectorObject::from_vector(storage, activation)?.into();
+
+            let value = PixelBenderType::from_avm2_value(
+                activation,
+                vector,
+                &PixelBenderTypeOpcode::TFloat2,
+            )?;
+            assert_eq!(value, PixelBenderType::TFloat2(1.5, -2.0));
+
+            let value = PixelBenderType::from_avm2_value(
+                activation,
+                vector,
+                &PixelBenderTypeOpcode::TInt2,
+            )?;
+            assert_eq!(value, PixelBenderType::TInt2(1, -2));
+
+            let result = PixelBenderType::from_avm2_value(
+                activation,
+                vector,
+                &PixelBenderTypeOpcode::TFloat3,
+            );
+            assert!(matches!(result, Err(Error::AvmError(_))));
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn bytearrays_are_read_from_position() {
+        with_avm2(PlayerBuilder::new(), |activation| {
+            let mut storage = ByteArrayStorage::new();
+            storage.set_endian(Endian::Little);
+            for value in [0.0, 1.5, -2.0, 0.25] {
+                storage.write_float(value).unwrap();
+            }
+            storage.set_position(4);
+            let bytes: Value = ByteArrayObject::from_storage(activation, storage)?.into();
+
+            let value = PixelBenderType::from_avm2_value(
+                activation,
+                bytes,
+                &PixelBenderTypeOpcode::TFloat2,
+            )?;
+            assert_eq!(value, PixelBenderType::TFloat2(1.5, -2.0));
+
+            // The position was advanced past the values that were read.
+            let value = PixelBenderType::from_avm2_value(
+                activation,
+                bytes,
+                &PixelBenderTypeOpcode::TFloat,
+            )?;
+            assert_eq!(value, PixelBenderType::TFloat(0.25));
+
+            let result =
+                PixelBenderType::from_avm2_value(activation, bytes, &PixelBenderTypeOpcode::TFloat);
+            assert!(matches!(result, Err(Error::AvmError(_))));
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn holes_are_zero() {
+        with_avm2(PlayerBuilder::new(), |activation| {
+            let storage = ArrayStorage::from_storage(vec![Some(1.into()), None, Some(3.into())]);
+            let array: Value = ArrayObject::from_storage(activation, storage).into();
+
+            let value = PixelBenderType::from_avm2_value(
+                activation,
+                array,
+                &PixelBenderTypeOpcode::TFloat3,
+            )?;
+            assert_eq!(value, PixelBenderType::TFloat3(1.0, 0.0, 3.0));
+
+            let value =
+                PixelBenderType::from_avm2_value(activation, array, &PixelBenderTypeOpcode::TInt3)?;
+            assert_eq!(value, PixelBenderType::TInt3(1, 0, 3));
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn malformed_arrays_throw() {
+        with_avm2(PlayerBuilder::new(), |activation| {
+            let cases = [
+                (vec![Some(1.into())], PixelBenderTypeOpcode::TFloat2),
+                (vec![Some(1.into()); 3], PixelBenderTypeOpcode::TFloat2x2),
+                (vec![Some(1.into())], PixelBenderTypeOpcode::TInt3),
+                (vec![Some(1.into())], PixelBenderTypeOpcode::TString),
+            ];
+            for (elements, kind) in cases {
+                let storage = ArrayStorage::from_storage(elements);
+                let array = ArrayObject::from_storage(activation, storage);
+                let result = PixelBenderType::from_avm2_value(activation, array.into(), &kind);
+                assert!(matches!(result, Err(Error::AvmError(_))), "{kind:?}");
+            }
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn array_length_must_match_kind() {
+        with_avm2(PlayerBuilder::new(), |activation| {
+            let storage = ArrayStorage::from_args(&[1.into(), 2.into(), 3.into()]);
+            let array: Value = ArrayObject::from_storage(activation, storage).into();
+
+            for (kind, expected) in [
+                (PixelBenderTypeOpcode::TFloat2, 2),
+                (PixelBenderTypeOpcode::TInt4, 4),
+                (PixelBenderTypeOpcode::TFloat3x3, 9),
+            ] {
+                let Err(Error::AvmError(error)) =
+                    PixelBenderType::from_avm2_value(activation, array, &kind)
+                else {
+                    panic!("{kind:?} should have thrown");
+                };
+                let message = AvmString::new_utf8(activation.gc(), "message");
+                let message = error.get_public_property(message, activation)?;
+                let message = message.coerce_to_string(activation)?.to_string();
+                assert!(
+                    message.ends_with(&format!(
+                        "A {kind} value needs {expected} components, but the array has 3."
+                    )),
+                    "{message}"
+                );
+            }
+
+            let value =
+                PixelBenderType::from_avm2_value(activation, array, &PixelBenderTypeOpcode::TInt3)?;
+            assert_eq!(value, PixelBenderType::TInt3(1, 2, 3));
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn empty_arrays_throw() {
+        with_avm2(PlayerBuilder::new(), |activation| {
+            let array: Value = ArrayObject::empty(activation).into();
+
+            // Like in Flash, an empty array is an invalid parameter rather than a zero vector.
+            for kind in [
+                PixelBenderTypeOpcode::TFloat,
+                PixelBenderTypeOpcode::TFloat4,
+                PixelBenderTypeOpcode::TFloat4x4,
+                PixelBenderTypeOpcode::TInt2,
+                PixelBenderTypeOpcode::TBool3,
+            ] {
+                let Err(Error::AvmError(error)) =
+                    PixelBenderType::from_avm2_value(activation, array, &kind)
+                else {
+                    panic!("{kind:?} should have thrown");
+                };
+                let error_id = AvmString::new_utf8(activation.gc(), "errorID");
+                let error_id = error.get_public_property(error_id, activation)?;
+                assert_eq!(error_id.coerce_to_i32(activation)?, 2004, "{kind:?}");
+            }
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn scalars_are_coerced_to_kind() {
+        with_avm2(PlayerBuilder::new(), |activation| {
+            for (value, kind, expected) in [
+                (
+                    Value::Bool(true),
+                    PixelBenderTypeOpcode::TFloat,
+                    PixelBenderType::TFloat(1.0),
+                ),
+                (
+                    Value::Bool(true),
+                    PixelBenderTypeOpcode::TInt,
+                    PixelBenderType::TInt(1),
+                ),
+                (
+                    Value::Number(2.9),
+                    PixelBenderTypeOpcode::TInt,
+                    PixelBenderType::TInt(2),
+                ),
+            ] {
+                assert_eq!(
+                    PixelBenderType::from_avm2_value(activation, value, &kind)?,
+                    expected,
+                    "{value:?} as {kind:?}"
+                );
+            }
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn scalars_need_single_component_kind() {
+        with_avm2(PlayerBuilder::new(), |activation| {
+            for (value, kind) in [
+                (Value::Bool(true), PixelBenderTypeOpcode::TBool2),
+                (Value::Number(0.5), PixelBenderTypeOpcode::TBool3),
+                (Value::Integer(1), PixelBenderTypeOpcode::TBool4),
+                (Value::Bool(false), PixelBenderTypeOpcode::TFloat2),
+                (Value::Integer(1), PixelBenderTypeOpcode::TInt3),
+            ] {
+                let Err(Error::AvmError(error)) =
+                    PixelBenderType::from_avm2_value(activation, value, &kind)
+                else {
+                    panic!("{value:?} as {kind:?} should have thrown");
+                };
+                let error_id = AvmString::new_utf8(activation.gc(), "errorID");
+                let error_id = error.get_public_property(error_id, activation)?;
+                assert_eq!(error_id.coerce_to_i32(activation)?, 2004, "{kind:?}");
+            }
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn avm2_type_names_are_parsed() {
+        for opcode in (0..=u8::MAX).filter_map(PixelBenderTypeOpcode::from_u8) {
+            assert_eq!(
+                type_opcode_from_avm2_name(&opcode.to_string()),
+                Some(opcode)
+            );
+        }
+        assert_eq!(type_opcode_from_avm2_name("Float"), None);
+    }
+
+    #[test]
+    fn values_are_described() {
+        assert_eq!(
+            describe_value(&PixelBenderType::TFloat3x3([
+                1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0
+            ])),
+            "TFloat3x3[1,0,0, 0,1,0, 0,0,1]"
+        );
+        assert_eq!(
+            describe_value(&PixelBenderType::TFloat2(0.5, -2.0)),
+            "TFloat2[0.5,-2]"
+        );
+        assert_eq!(describe_value(&PixelBenderType::TInt(-3)), "TInt[-3]");
+        assert_eq!(
+            describe_value(&PixelBenderType::TBool2(true, false)),
+            "TBool2[true,false]"
+        );
+        assert_eq!(
+            describe_value(&PixelBenderType::TString("a \"b\"".to_string())),
+            "TString[\"a \\\"b\\\"\"]"
+        );
+    }
+
+    /// Measures how long it takes to marshal a `TFloat4x4` from AVM2, checking that the
+    /// fast paths for plain numbers and `ByteArray`s beat coercing every component.
+    ///
+    /// Run with `cargo test -p ruffle_core --release -- --ignored tfloat4x4_marshalling`.
+    #[test]
+    #[ignore]
+    fn tfloat4x4_marshalling_fast_paths() {
+        const ITERATIONS: u32 = 100_000;
+
+        fn measure<'gc>(
+            activation: &mut Activation<'_, 'gc>,
+            value: Value<'gc>,
+        ) -> std::time::Duration {
+            let start = std::time::Instant::now();
+            for _ in 0..ITERATIONS {
+                if let Some(bytes) = value.as_object().and_then(|o| o.as_bytearray_object()) {
+                    // Reading consumes the data, so every run starts over.
+                    bytes.storage().set_position(0);
+                }
+                let result = PixelBenderType::from_avm2_value(
+                    activation,
+                    value,
+                    &PixelBenderTypeOpcode::TFloat4x4,
+                );
+                assert!(std::hint::black_box(result).is_ok());
+            }
+            start.elapsed() / ITERATIONS
+        }
+
+        let floats: [f32; 16] = std::array::from_fn(|i| i as f32 * 0.5 + 0.25);
+        with_avm2(PlayerBuilder::new(), |activation| {
+            let numbers: ArrayStorage = floats.iter().map(|f| f64::from(*f)).collect();
+            let numbers = ArrayObject::from_storage(activation, numbers);
+            let numbers = measure(activation, numbers.into());
+
+            // Strings can't take the fast path, so every component is coerced on its own.
+            let strings: ArrayStorage = floats
+                .iter()
+                .map(|f| AvmString::new_utf8(activation.gc(), f.to_string()))
+                .collect();
+            let strings = ArrayObject::from_storage(activation, strings);
+            let strings = measure(activation, strings.into());
+
+            let mut storage = ByteArrayStorage::new();
+            for float in floats {
+                storage
+                    .write_float(float)
+                    .expect("Floats should fit in a ByteArray");
+            }
+            let bytes = ByteArrayObject::from_storage(activation, storage)?;
+            let bytes = measure(activation, bytes.into());
+
+            assert!(
+                numbers < strings && bytes < strings,
+                "Per value: {numbers:?} from numbers, {bytes:?} from a ByteArray, {strings:?} from strings"
+            );
+            Ok(())
+        });
+    }
 }
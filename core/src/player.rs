@@ -9,11 +9,14 @@ use crate::avm2::{Activation as Avm2Activation, Avm2, CallStack};
 use crate::backend::ui::FontDefinition;
 use crate::backend::{
     audio::{AudioBackend, AudioManager},
+    camera::{CameraBackend, CameraPermission},
     log::LogBackend,
+    microphone::MicrophoneBackend,
     navigator::{NavigatorBackend, Request},
     storage::StorageBackend,
     ui::{MouseCursor, UiBackend},
 };
+use crate::camera::Cameras;
 use crate::compatibility_rules::CompatibilityRules;
 use crate::config::Letterbox;
 use crate::context::{ActionQueue, ActionType, RenderContext, UpdateContext};
@@ -39,6 +42,7 @@ use crate::limits::ExecutionLimit;
 use crate::loader::{LoadBehavior, LoadManager};
 use crate::local_connection::LocalConnections;
 use crate::locale::get_current_date_time;
+use crate::microphone::Microphones;
 use crate::net_connection::NetConnections;
 use crate::prelude::*;
 use crate::socket::Sockets;
@@ -193,6 +197,12 @@ struct GcRootData<'gc> {
 
     local_connections: LocalConnections<'gc>,
 
+    /// List of active Camera objects.
+    cameras: Cameras<'gc>,
+
+    /// List of Microphone objects that are capturing audio.
+    microphones: Microphones<'gc>,
+
     /// Dynamic root for allowing handles to GC objects to exist outside of the GC.
     dynamic_root: DynamicRootSet<'gc>,
 
@@ -234,6 +244,8 @@ impl<'gc> GcRootData<'gc> {
         &mut Sockets<'gc>,
         &mut NetConnections<'gc>,
         &mut LocalConnections<'gc>,
+        &mut Cameras<'gc>,
+        &mut Microphones<'gc>,
         &mut Vec<PostFrameCallback<'gc>>,
         &mut MouseData<'gc>,
         DynamicRootSet<'gc>,
@@ -258,6 +270,8 @@ impl<'gc> GcRootData<'gc> {
             &mut self.sockets,
             &mut self.net_connections,
             &mut self.local_connections,
+            &mut self.cameras,
+            &mut self.microphones,
             &mut self.post_frame_callbacks,
             &mut self.mouse_data,
             self.dynamic_root,
@@ -302,6 +316,8 @@ pub struct Player {
 
     renderer: Box<dyn RenderBackend>,
     audio: Box<dyn AudioBackend>,
+    camera: Box<dyn CameraBackend>,
+    microphone: Box<dyn MicrophoneBackend>,
     navigator: Box<dyn NavigatorBackend>,
     storage: Box<dyn StorageBackend>,
     log: Box<dyn LogBackend>,
@@ -379,6 +395,9 @@ pub struct Player {
     /// Any compatibility rules to apply for this movie.
     compatibility_rules: CompatibilityRules,
 
+    /// Whether the movie may capture from the user's cameras.
+    camera_permission: CameraPermission,
+
     /// Sends notifications back from the core player to the frontend.
     notification_sender: Option<Sender<PlayerNotification>>,
 
@@ -566,6 +585,8 @@ impl Player {
 
         self.update_sockets();
         self.update_net_connections();
+        self.update_cameras();
+        self.update_microphones();
         self.update_timers(dt);
         self.update(|context| {
             StreamManager::tick(context, dt);
@@ -2069,6 +2090,46 @@ impl Player {
         std::mem::swap(&mut self.storage, storage);
     }
 
+    pub fn camera(&self) -> &dyn CameraBackend {
+        &*self.camera
+    }
+
+    pub fn camera_mut(&mut self) -> &mut dyn CameraBackend {
+        &mut *self.camera
+    }
+
+    pub fn microphone(&self) -> &dyn MicrophoneBackend {
+        &*self.microphone
+    }
+
+    pub fn microphone_mut(&mut self) -> &mut dyn MicrophoneBackend {
+        &mut *self.microphone
+    }
+
+    /// Replaces the camera backend, returning the previous one.
+    ///
+    /// Cameras opened through the previous backend are closed and stop capturing,
+    /// and `Camera.getCamera` opens devices through the new backend from now on.
+    pub fn set_camera(&mut self, camera: Box<dyn CameraBackend>) -> Box<dyn CameraBackend> {
+        self.mutate_with_update_context(|context| Cameras::close_all(context));
+        std::mem::replace(&mut self.camera, camera)
+    }
+
+    /// Answers whether the movie may capture from the user's cameras.
+    ///
+    /// Cameras that were already handed out are opened or closed accordingly, and
+    /// receive a `Camera.Unmuted` or `Camera.Muted` status event. Microphones stop
+    /// capturing unless access is granted.
+    pub fn set_camera_permission(&mut self, camera_permission: CameraPermission) {
+        let previous = std::mem::replace(&mut self.camera_permission, camera_permission);
+        self.mutate_with_update_context(|context| {
+            Cameras::resolve_permission(context, previous);
+            if context.camera_permission != CameraPermission::Granted {
+                Microphones::close_all(context);
+            }
+        });
+    }
+
     pub fn ui(&self) -> &dyn UiBackend {
         &*self.ui
     }
@@ -2188,6 +2249,8 @@ impl Player {
                 sockets,
                 net_connections,
                 local_connections,
+                cameras,
+                microphones,
                 post_frame_callbacks,
                 mouse_data,
                 dynamic_root,
@@ -2201,6 +2264,8 @@ impl Player {
                 rng: &mut this.rng,
                 renderer: this.renderer.deref_mut(),
                 audio: this.audio.deref_mut(),
+                camera: this.camera.deref_mut(),
+                microphone: this.microphone.deref_mut(),
                 navigator: this.navigator.deref_mut(),
                 ui: this.ui.deref_mut(),
                 action_queue,
@@ -2237,6 +2302,7 @@ impl Player {
                 audio_manager,
                 frame_rate: &mut this.frame_rate,
                 forced_frame_rate: this.forced_frame_rate,
+                camera_permission: this.camera_permission,
                 actions_since_timeout_check: &mut this.actions_since_timeout_check,
                 frame_phase: &mut this.frame_phase,
                 stub_tracker: &mut this.stub_tracker,
@@ -2244,6 +2310,8 @@ impl Player {
                 sockets,
                 net_connections,
                 local_connections,
+                cameras,
+                microphones,
                 dynamic_root,
                 post_frame_callbacks,
                 notification_sender: this.notification_sender.as_ref(),
@@ -2368,6 +2436,20 @@ impl Player {
         })
     }
 
+    /// Update active Cameras, uploading any newly captured frames.
+    pub fn update_cameras(&mut self) {
+        self.mutate_with_update_context(|context| {
+            Cameras::update_cameras(context);
+        })
+    }
+
+    /// Update capturing Microphones, dispatching any newly captured audio.
+    pub fn update_microphones(&mut self) {
+        self.mutate_with_update_context(|context| {
+            Microphones::update_microphones(context);
+        })
+    }
+
     /// Returns whether this player consumes mouse wheel events.
     /// Used by web to prevent scrolling.
     pub fn should_prevent_scrolling(&mut self) -> bool {
@@ -2444,6 +2526,13 @@ impl Player {
 impl Drop for Player {
     fn drop(&mut self) {
         self.flush_shared_objects();
+
+        // Backends may capture on threads of their own, which have to be stopped
+        // before the devices can be used by anything else.
+        self.mutate_with_update_context(|context| {
+            Cameras::close_all(context);
+            Microphones::close_all(context);
+        });
     }
 }
 
@@ -2453,7 +2542,9 @@ pub struct PlayerBuilder {
 
     // Backends
     audio: Option<Box<dyn AudioBackend>>,
+    camera: Option<Box<dyn CameraBackend>>,
     log: Option<Box<dyn LogBackend>>,
+    microphone: Option<Box<dyn MicrophoneBackend>>,
     navigator: Option<Box<dyn NavigatorBackend>>,
     renderer: Option<Box<dyn RenderBackend>>,
     storage: Option<Box<dyn StorageBackend>>,
@@ -2486,6 +2577,7 @@ pub struct PlayerBuilder {
     quality: StageQuality,
     page_url: Option<String>,
     frame_rate: Option<f64>,
+    camera_permission: CameraPermission,
     external_interface_provider: Option<Box<dyn ExternalInterfaceProvider>>,
     fs_command_provider: Box<dyn FsCommandProvider>,
     #[cfg(feature = "known_stubs")]
@@ -2504,7 +2596,9 @@ impl PlayerBuilder {
             movie: None,
 
             audio: None,
+            camera: None,
             log: None,
+            microphone: None,
             navigator: None,
             renderer: None,
             storage: None,
@@ -2540,6 +2634,7 @@ impl PlayerBuilder {
             quality: StageQuality::High,
             page_url: None,
             frame_rate: None,
+            camera_permission: CameraPermission::default(),
             external_interface_provider: None,
             fs_command_provider: Box::new(NullFsCommandProvider),
             #[cfg(feature = "known_stubs")]
@@ -2569,6 +2664,34 @@ impl PlayerBuilder {
         self
     }
 
+    /// Sets the camera backend of the player.
+    #[inline]
+    pub fn with_camera(mut self, camera: impl 'static + CameraBackend) -> Self {
+        self.camera = Some(Box::new(camera));
+        self
+    }
+
+    /// Sets the camera backend of the player.
+    #[inline]
+    pub fn with_boxed_camera(mut self, camera: Box<dyn CameraBackend>) -> Self {
+        self.camera = Some(camera);
+        self
+    }
+
+    /// Sets the microphone backend of the player.
+    #[inline]
+    pub fn with_microphone(mut self, microphone: impl 'static + MicrophoneBackend) -> Self {
+        self.microphone = Some(Box::new(microphone));
+        self
+    }
+
+    /// Sets the microphone backend of the player.
+    #[inline]
+    pub fn with_boxed_microphone(mut self, microphone: Box<dyn MicrophoneBackend>) -> Self {
+        self.microphone = Some(microphone);
+        self
+    }
+
     /// Sets the logging backend of the player.
     #[inline]
     pub fn with_log(mut self, log: impl 'static + LogBackend) -> Self {
@@ -2730,6 +2853,12 @@ impl PlayerBuilder {
         self
     }
 
+    /// Sets whether the movie may capture from the user's cameras.
+    pub fn with_camera_permission(mut self, camera_permission: CameraPermission) -> Self {
+        self.camera_permission = camera_permission;
+        self
+    }
+
     /// Sets and locks the player's frame rate. If None is provided, this has no effect.
     pub fn with_frame_rate(mut self, frame_rate: Option<f64>) -> Self {
         self.frame_rate = frame_rate;
@@ -2816,6 +2945,8 @@ impl PlayerBuilder {
             sockets: Sockets::empty(),
             net_connections: NetConnections::default(),
             local_connections: LocalConnections::empty(),
+            cameras: Cameras::empty(),
+            microphones: Microphones::empty(),
             dynamic_root: DynamicRootSet::new(gc_context),
             post_frame_callbacks: Vec::new(),
         };
@@ -2833,9 +2964,15 @@ impl PlayerBuilder {
         let audio = self
             .audio
             .unwrap_or_else(|| Box::new(audio::NullAudioBackend::new()));
+        let camera = self
+            .camera
+            .unwrap_or_else(|| Box::new(camera::NullCameraBackend::new()));
         let log = self
             .log
             .unwrap_or_else(|| Box::new(log::NullLogBackend::new()));
+        let microphone = self
+            .microphone
+            .unwrap_or_else(|| Box::new(microphone::NullMicrophoneBackend::new()));
         let navigator = self
             .navigator
             .unwrap_or_else(|| Box::new(navigator::NullNavigatorBackend::new()));
@@ -2867,7 +3004,9 @@ impl PlayerBuilder {
             Mutex::new(Player {
                 // Backends
                 audio,
+                camera,
                 log,
+                microphone,
                 navigator,
                 renderer,
                 storage,
@@ -2916,6 +3055,7 @@ impl PlayerBuilder {
                 load_behavior: self.load_behavior,
                 spoofed_url: self.spoofed_url.clone(),
                 compatibility_rules: self.compatibility_rules.clone(),
+                camera_permission: self.camera_permission,
                 stub_tracker: StubCollection::new(),
                 notification_sender: self.notification_sender,
                 #[cfg(feature = "egui")]
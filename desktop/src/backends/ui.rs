@@ -10,6 +10,7 @@ use fontdb::{FaceInfo, Family};
 use rfd::{
     AsyncFileDialog, FileHandle, MessageButtons, MessageDialog, MessageDialogResult, MessageLevel,
 };
+use ruffle_core::backend::camera::{CameraAccess, CameraDevice};
 use ruffle_core::backend::ui::{
     DialogLoaderError, DialogResultFuture, FileDialogResult, FileFilter, FontDefinition,
     FullscreenError, LanguageIdentifier, MouseCursor, UiBackend,
@@ -394,6 +395,11 @@ impl UiBackend for DesktopUiBackend {
     }
 
     fn close_file_dialog(&mut self) {}
+
+    fn request_camera_access(&mut self, _device: &CameraDevice) -> CameraAccess {
+        // Movies only get this far with `--allow-camera`, which covers every device.
+        CameraAccess::AlwaysAllow
+    }
 }
 
 fn load_font_from_file(
@@ -243,6 +243,26 @@ pub struct Opt {
     /// (like inlining constant pool entries) can't be disabled.
     #[clap(long)]
     pub no_avm2_optimizer: bool,
+
+    /// How long to wait for each camera to respond while looking for cameras, in milliseconds.
+    /// Cameras that take longer than this are skipped.
+    #[clap(long, default_value = "500", value_parser(parse_duration_millis))]
+    pub camera_probe_timeout: Duration,
+
+    /// How many camera devices to look at, starting from the first one.
+    /// Increase this if a camera, like a virtual one, is numbered higher than that.
+    #[clap(long, default_value = "10")]
+    pub camera_scan_limit: usize,
+
+    /// Prints details about every camera device that Ruffle looks at, and why it is or
+    /// isn't used, then exits. Useful for reporting issues with cameras not being detected.
+    #[clap(long)]
+    pub list_cameras: bool,
+
+    /// Lets movies capture from cameras and microphones, as if Flash Player's privacy
+    /// dialog was answered with "Allow". Without this, they're always muted.
+    #[clap(long)]
+    pub allow_camera: bool,
 }
 
 fn parse_movie_file_or_url(path: &str) -> Result<Url, Error> {
@@ -253,6 +273,10 @@ fn parse_duration_seconds(value: &str) -> Result<Duration, Error> {
     Ok(Duration::from_secs_f64(value.parse()?))
 }
 
+fn parse_duration_millis(value: &str) -> Result<Duration, Error> {
+    Ok(Duration::from_millis(value.parse()?))
+}
+
 fn parse_align(value: &str) -> Result<StageAlign, Error> {
     value
         .parse()
@@ -2,8 +2,8 @@ use crate::cli::{GameModePreference, OpenUrlMode};
 use crate::gui::{available_languages, optional_text, text, ThemePreference};
 use crate::log::FilenamePattern;
 use crate::preferences::{storage::StorageBackend, GlobalPreferences};
-use cpal::traits::{DeviceTrait, HostTrait};
 use egui::{Align2, Button, Checkbox, ComboBox, DragValue, Grid, Ui, Widget, Window};
+use ruffle_frontend_utils::backends::audio_devices::list_output_devices;
 use ruffle_render_wgpu::clap::{GraphicsBackend, PowerPreference};
 use std::borrow::Cow;
 use unic_langid::LanguageIdentifier;
@@ -60,15 +60,10 @@ impl PreferencesDialog {
     pub fn new(preferences: GlobalPreferences) -> Self {
         let available_backends = find_available_graphics_backends();
 
-        let audio_host = cpal::default_host();
-        let mut available_output_devices = Vec::new();
-        if let Ok(devices) = audio_host.output_devices() {
-            for device in devices {
-                if let Ok(name) = device.name() {
-                    available_output_devices.push(name);
-                }
-            }
-        }
+        let available_output_devices = list_output_devices(&cpal::default_host())
+            .into_iter()
+            .map(|(_, name)| name)
+            .collect();
 
         Self {
             available_backends,
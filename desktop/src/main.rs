@@ -148,11 +148,29 @@ fn shutdown() {
     }
 }
 
+#[cfg_attr(not(all(feature = "camera_v4l", target_os = "linux")), allow(unused))]
+fn list_cameras(scan_limit: usize) {
+    #[cfg(all(feature = "camera_v4l", target_os = "linux"))]
+    for report in ruffle_frontend_utils::backends::camera::diagnose_devices(scan_limit) {
+        println!("{report}");
+    }
+
+    #[cfg(all(not(feature = "camera_v4l"), target_os = "linux"))]
+    println!("Camera support was disabled when building Ruffle");
+
+    #[cfg(not(target_os = "linux"))]
+    println!("Camera diagnostics are only available on Linux");
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Error> {
     init();
 
     let opt = Opt::parse();
+    if opt.list_cameras {
+        list_cameras(opt.camera_scan_limit);
+        return Ok(());
+    }
     let preferences = GlobalPreferences::load(opt.clone())?;
 
     let logs_path = &preferences.cli.cache_directory.join("log");
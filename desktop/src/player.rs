@@ -9,6 +9,7 @@ use crate::gui::{FilePicker, MovieView};
 use crate::preferences::GlobalPreferences;
 use crate::{CALLSTACK, RENDER_INFO, SWF_INFO};
 use anyhow::anyhow;
+use ruffle_core::backend::camera::CameraPermission;
 use ruffle_core::backend::navigator::SocketMode;
 use ruffle_core::config::Letterbox;
 use ruffle_core::events::{GamepadButton, KeyCode};
@@ -51,6 +52,9 @@ pub struct LaunchOptions {
     pub filesystem_access_mode: FilesystemAccessMode,
     pub gamepad_button_mapping: HashMap<GamepadButton, KeyCode>,
     pub avm2_optimizer_enabled: bool,
+    pub camera_probe_timeout: Duration,
+    pub camera_scan_limit: usize,
+    pub allow_camera: bool,
 }
 
 impl From<&GlobalPreferences> for LaunchOptions {
@@ -101,6 +105,9 @@ impl From<&GlobalPreferences> for LaunchOptions {
             tcp_connections: value.cli.tcp_connections,
             gamepad_button_mapping: HashMap::from_iter(value.cli.gamepad_button.iter().cloned()),
             avm2_optimizer_enabled: !value.cli.no_avm2_optimizer,
+            camera_probe_timeout: value.cli.camera_probe_timeout,
+            camera_scan_limit: value.cli.camera_scan_limit,
+            allow_camera: value.cli.allow_camera,
         }
     }
 }
@@ -150,6 +157,45 @@ impl ActivePlayer {
             }
         };
 
+        #[cfg(all(feature = "camera_v4l", target_os = "linux"))]
+        {
+            use ruffle_core::backend::camera::SharedCameraBackend;
+            use ruffle_frontend_utils::backends::camera::V4lCameraBackend;
+            builder = builder.with_camera(SharedCameraBackend::new(
+                V4lCameraBackend::new()
+                    .with_probe_timeout(opt.camera_probe_timeout)
+                    .with_scan_limit(opt.camera_scan_limit),
+            ));
+        }
+
+        #[cfg(target_os = "windows")]
+        {
+            use ruffle_core::backend::camera::SharedCameraBackend;
+            use ruffle_frontend_utils::backends::camera::MediaFoundationCameraBackend;
+            builder =
+                builder.with_camera(SharedCameraBackend::new(MediaFoundationCameraBackend::new()));
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            use ruffle_core::backend::camera::SharedCameraBackend;
+            use ruffle_frontend_utils::backends::camera::AvFoundationCameraBackend;
+            builder =
+                builder.with_camera(SharedCameraBackend::new(AvFoundationCameraBackend::new()));
+        }
+
+        {
+            use ruffle_frontend_utils::backends::microphone::CpalMicrophoneBackend;
+            builder = builder.with_microphone(CpalMicrophoneBackend::new());
+        }
+
+        // There's no privacy dialog yet, so the answer comes from the command line.
+        builder = builder.with_camera_permission(if opt.allow_camera {
+            CameraPermission::Granted
+        } else {
+            CameraPermission::Denied
+        });
+
         let mut content = PlayingContent::DirectFile(movie_url.clone());
         if movie_url.scheme() == "file" {
             if let Ok(path) = movie_url.to_file_path() {
@@ -207,6 +253,9 @@ impl ActivePlayer {
                     filesystem_access_mode: opt.filesystem_access_mode,
                     gamepad_button_mapping: opt.gamepad_button_mapping.clone(),
                     avm2_optimizer_enabled: opt.avm2_optimizer_enabled,
+                    camera_probe_timeout: opt.camera_probe_timeout,
+                    camera_scan_limit: opt.camera_scan_limit,
+                    allow_camera: opt.allow_camera,
                 })
             }
         };
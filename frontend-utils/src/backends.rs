@@ -1,5 +1,9 @@
 #[cfg(feature = "cpal")]
 pub mod audio;
+#[cfg(feature = "cpal")]
+pub mod audio_devices;
+pub mod camera;
 pub mod executor;
+pub mod microphone;
 pub mod navigator;
 pub mod storage;
@@ -0,0 +1,48 @@
+//! Enumeration of the audio devices of cpal, shared by microphones and output device selection
+
+use cpal::traits::{DeviceTrait, HostTrait};
+
+/// Lists the devices that can capture audio, in the order of `list_input_devices`.
+pub fn input_devices(host: &cpal::Host) -> Vec<cpal::Device> {
+    match host.input_devices() {
+        Ok(devices) => devices.collect(),
+        Err(e) => {
+            tracing::warn!("Failed to list audio input devices: {e}");
+            Vec::new()
+        }
+    }
+}
+
+/// Lists the devices that can play audio, in the order of `list_output_devices`.
+pub fn output_devices(host: &cpal::Host) -> Vec<cpal::Device> {
+    match host.output_devices() {
+        Ok(devices) => devices.collect(),
+        Err(e) => {
+            tracing::warn!("Failed to list audio output devices: {e}");
+            Vec::new()
+        }
+    }
+}
+
+/// Lists the index and name of every device that can capture audio.
+///
+/// The index is the position of the device in `input_devices`.
+pub fn list_input_devices(host: &cpal::Host) -> Vec<(u32, String)> {
+    named(input_devices(host))
+}
+
+/// Lists the index and name of every device that can play audio.
+///
+/// The index is the position of the device in `output_devices`.
+pub fn list_output_devices(host: &cpal::Host) -> Vec<(u32, String)> {
+    named(output_devices(host))
+}
+
+/// Devices without a name can't be told apart, so they're left out.
+fn named(devices: Vec<cpal::Device>) -> Vec<(u32, String)> {
+    devices
+        .iter()
+        .enumerate()
+        .filter_map(|(index, device)| Some((index as u32, device.name().ok()?)))
+        .collect()
+}
@@ -0,0 +1,25 @@
+#[cfg(all(feature = "camera_avfoundation", target_os = "macos"))]
+mod avfoundation;
+#[cfg(any(
+    all(feature = "camera_v4l", target_os = "linux"),
+    all(feature = "camera_mf", target_os = "windows")
+))]
+mod frame_rate;
+#[cfg(all(feature = "camera_v4l", target_os = "linux"))]
+mod frame_ring;
+#[cfg(all(feature = "camera_mf", target_os = "windows"))]
+mod media_foundation;
+#[cfg(all(feature = "camera_v4l", target_os = "linux"))]
+mod pixel_format;
+#[cfg(all(feature = "camera_v4l", target_os = "linux"))]
+mod video4linux;
+
+#[cfg(all(feature = "camera_avfoundation", target_os = "macos"))]
+pub use avfoundation::AvFoundationCameraBackend;
+#[cfg(all(feature = "camera_mf", target_os = "windows"))]
+pub use media_foundation::MediaFoundationCameraBackend;
+#[cfg(all(feature = "camera_v4l", target_os = "linux"))]
+pub use video4linux::{
+    diagnose_devices, is_capture_device, DeviceReport, DeviceStatus, OpenRetry, V4lCameraBackend,
+    DEFAULT_SCAN_LIMIT,
+};
@@ -0,0 +1,140 @@
+use objc2_av_foundation::{AVCaptureDevice, AVMediaTypeVideo};
+use ruffle_core::backend::camera::{
+    CameraBackend, CameraDevice, CameraError, CameraEvent, CameraFormat, CameraFrame, CameraHandle,
+    CameraMode,
+};
+
+/// Camera backend listing AVFoundation devices.
+///
+/// Capturing isn't implemented yet, so devices can't be opened, and the movie is told
+/// that the camera failed instead of showing a blank `Video`.
+pub struct AvFoundationCameraBackend {
+    /// The unique ids of every device seen so far, indexed by their camera id.
+    ///
+    /// Devices are only ever appended, so that a camera keeps its index in
+    /// `Camera.names` for the whole session, even if other devices are unplugged.
+    unique_ids: Vec<String>,
+}
+
+impl AvFoundationCameraBackend {
+    pub fn new() -> Self {
+        Self {
+            unique_ids: Vec::new(),
+        }
+    }
+
+    /// Returns the stable id of a device, assigning a new one if it wasn't seen before.
+    fn device_id(&mut self, unique_id: String) -> u32 {
+        let index = match self.unique_ids.iter().position(|id| *id == unique_id) {
+            Some(index) => index,
+            None => {
+                self.unique_ids.push(unique_id);
+                self.unique_ids.len() - 1
+            }
+        };
+        index as u32
+    }
+}
+
+impl Default for AvFoundationCameraBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CameraBackend for AvFoundationCameraBackend {
+    fn list_devices(&mut self) -> Vec<CameraDevice> {
+        let mut devices: Vec<_> = video_devices()
+            .into_iter()
+            .map(|(unique_id, name)| CameraDevice {
+                id: self.device_id(unique_id),
+                name,
+            })
+            .collect();
+        // Keep the order of `Camera.names` stable, even if AVFoundation reorders devices.
+        devices.sort_by_key(|device| device.id);
+        devices
+    }
+
+    fn open(&mut self, id: u32) -> Result<CameraHandle, CameraError> {
+        let unique_id = self
+            .unique_ids
+            .get(id as usize)
+            .ok_or(CameraError::NotFound)?;
+        if !video_devices()
+            .iter()
+            .any(|(device, _)| device == unique_id)
+        {
+            return Err(CameraError::NotFound);
+        }
+        tracing::warn!("Capturing from cameras is not supported on macOS yet");
+        Err(CameraError::Unsupported)
+    }
+
+    fn close(&mut self, _handle: CameraHandle) {}
+
+    fn format(&self, _handle: CameraHandle) -> Option<CameraFormat> {
+        None
+    }
+
+    fn supported_modes(&self, _handle: CameraHandle) -> Vec<CameraMode> {
+        Vec::new()
+    }
+
+    fn set_mode(&mut self, _handle: CameraHandle, _mode: CameraMode) -> Result<(), CameraError> {
+        Err(CameraError::Other(
+            "Changing the camera mode is not supported".to_string(),
+        ))
+    }
+
+    fn set_frozen(&mut self, _handle: CameraHandle, _frozen: bool) {}
+
+    fn current_fps(&self, _handle: CameraHandle) -> f64 {
+        0.0
+    }
+
+    fn poll_event(&mut self, _handle: CameraHandle) -> Option<CameraEvent> {
+        None
+    }
+
+    fn next_frame(&mut self, _handle: CameraHandle) -> Option<CameraFrame> {
+        None
+    }
+}
+
+/// Lists the unique id and localized name of every connected video device.
+fn video_devices() -> Vec<(String, String)> {
+    let Some(media_type) = (unsafe { AVMediaTypeVideo }) else {
+        return Vec::new();
+    };
+    // The replacement, `AVCaptureDeviceDiscoverySession`, needs every device type to be
+    // listed explicitly, and new types keep getting added.
+    #[allow(deprecated)]
+    let devices = unsafe { AVCaptureDevice::devicesWithMediaType(media_type) };
+    devices
+        .iter()
+        .map(|device| unsafe {
+            (
+                device.uniqueID().to_string(),
+                device.localizedName().to_string().trim().to_string(),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn device_ids_are_stable() {
+        let mut backend = AvFoundationCameraBackend::new();
+        assert_eq!(backend.device_id("built-in".to_string()), 0);
+        assert_eq!(backend.device_id("usb".to_string()), 1);
+
+        // A device that shows up again keeps its id, new ones are added at the end.
+        assert_eq!(backend.device_id("usb".to_string()), 1);
+        assert_eq!(backend.device_id("continuity".to_string()), 2);
+        assert_eq!(backend.device_id("built-in".to_string()), 0);
+    }
+}
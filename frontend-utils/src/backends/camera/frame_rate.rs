@@ -0,0 +1,57 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Measures how many frames a device captured during the last second.
+#[derive(Default)]
+pub struct FrameRate {
+    captured: VecDeque<Instant>,
+}
+
+impl FrameRate {
+    const WINDOW: Duration = Duration::from_secs(1);
+
+    pub fn record(&mut self, now: Instant) {
+        self.captured.push_back(now);
+        while self
+            .captured
+            .front()
+            .is_some_and(|&captured| now.duration_since(captured) >= Self::WINDOW)
+        {
+            self.captured.pop_front();
+        }
+    }
+
+    pub fn per_second(&self, now: Instant) -> f64 {
+        self.captured
+            .iter()
+            .filter(|&&captured| now.duration_since(captured) < Self::WINDOW)
+            .count() as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frame_rate_counts_last_second() {
+        let start = Instant::now();
+        let mut frame_rate = FrameRate::default();
+        assert_eq!(frame_rate.per_second(start), 0.0);
+
+        for frame in 0..30 {
+            frame_rate.record(start + Duration::from_millis(frame * 50));
+        }
+        // Frames 10 to 29 were captured within the second before the last one.
+        let last = start + Duration::from_millis(29 * 50);
+        assert_eq!(frame_rate.per_second(last), 20.0);
+        assert_eq!(frame_rate.captured.len(), 20);
+
+        // Without new frames, the rate drops off.
+        assert_eq!(
+            frame_rate.per_second(last + Duration::from_millis(500)),
+            10.0
+        );
+        assert_eq!(frame_rate.per_second(last + Duration::from_secs(1)), 0.0);
+    }
+}
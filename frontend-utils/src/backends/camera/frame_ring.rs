@@ -0,0 +1,56 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+/// Frames handed from a capture thread to the player, of which only the newest few are kept.
+///
+/// The capture thread never waits for the player: once every slot is taken, the
+/// oldest frame is dropped to make room for the next one.
+pub struct FrameRing<T> {
+    slots: Mutex<VecDeque<T>>,
+    capacity: usize,
+}
+
+impl<T> FrameRing<T> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            slots: Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity,
+        }
+    }
+
+    pub fn push(&self, frame: T) {
+        let mut slots = self.slots.lock().expect("frame ring lock poisoned");
+        if slots.len() >= self.capacity {
+            slots.pop_front();
+        }
+        slots.push_back(frame);
+    }
+
+    /// Takes the newest frame, dropping the older ones as they're outdated.
+    pub fn take_latest(&self) -> Option<T> {
+        let mut slots = self.slots.lock().expect("frame ring lock poisoned");
+        let latest = slots.pop_back();
+        slots.clear();
+        latest
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_newest_frames_are_kept() {
+        let ring = FrameRing::new(3);
+        assert_eq!(ring.take_latest(), None);
+
+        for frame in 0..5 {
+            ring.push(frame);
+        }
+        assert_eq!(ring.slots.lock().unwrap().len(), 3);
+        assert_eq!(ring.take_latest(), Some(4));
+
+        // Older frames are dropped along with the one that was taken.
+        assert_eq!(ring.take_latest(), None);
+    }
+}
@@ -0,0 +1,512 @@
+use super::frame_rate::FrameRate;
+use ruffle_core::backend::camera::{
+    CameraBackend, CameraDevice, CameraError, CameraEvent, CameraFormat, CameraFrame, CameraHandle,
+    CameraMode,
+};
+use slotmap::SlotMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use windows::core::PWSTR;
+use windows::Win32::Foundation::RPC_E_CHANGED_MODE;
+use windows::Win32::Media::MediaFoundation::{
+    IMFActivate, IMFAttributes, IMFMediaSource, IMFSourceReader, MFCreateAttributes,
+    MFCreateMediaType, MFCreateSourceReaderFromMediaSource, MFEnumDeviceSources, MFMediaType_Video,
+    MFShutdown, MFStartup, MFVideoFormat_RGB32, MFSTARTUP_FULL,
+    MF_DEVSOURCE_ATTRIBUTE_FRIENDLY_NAME, MF_DEVSOURCE_ATTRIBUTE_SOURCE_TYPE,
+    MF_DEVSOURCE_ATTRIBUTE_SOURCE_TYPE_VIDCAP_GUID,
+    MF_DEVSOURCE_ATTRIBUTE_SOURCE_TYPE_VIDCAP_SYMBOLIC_LINK, MF_MT_DEFAULT_STRIDE,
+    MF_MT_FRAME_SIZE, MF_MT_MAJOR_TYPE, MF_MT_SUBTYPE, MF_SOURCE_READERF_ENDOFSTREAM,
+    MF_SOURCE_READER_ENABLE_VIDEO_PROCESSING, MF_SOURCE_READER_FIRST_VIDEO_STREAM, MF_VERSION,
+};
+use windows::Win32::System::Com::{
+    CoInitializeEx, CoTaskMemFree, CoUninitialize, COINIT_MULTITHREADED,
+};
+
+/// The stream index of the first video stream, as expected by `IMFSourceReader`.
+const VIDEO_STREAM: u32 = MF_SOURCE_READER_FIRST_VIDEO_STREAM.0 as u32;
+
+/// How long closing a camera waits for its capture thread to exit.
+///
+/// `ReadSample` only returns once the device delivers a frame, which a stalled device
+/// never does, so the thread is left to exit on its own after this.
+const STOP_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// A video capture device, as enumerated by Media Foundation.
+struct DeviceSource {
+    name: String,
+
+    /// Identifies the device across enumerations, unlike its position in the list.
+    symbolic_link: String,
+}
+
+/// What the capture thread of an open device shares with the backend.
+#[derive(Default)]
+struct Capture {
+    format: Option<CameraFormat>,
+    frame: Option<CameraFrame>,
+    frame_rate: FrameRate,
+}
+
+struct OpenCamera {
+    capture: Arc<Mutex<Capture>>,
+    stop: Arc<AtomicBool>,
+    thread: Option<thread::JoinHandle<()>>,
+
+    /// Disconnects once the capture thread exits.
+    exited: mpsc::Receiver<()>,
+
+    /// The format that was last reported to the player.
+    format: CameraFormat,
+
+    /// Whether captured frames are dropped instead of being delivered.
+    frozen: bool,
+}
+
+impl Drop for OpenCamera {
+    fn drop(&mut self) {
+        // The thread checks this after every frame, so it exits within a frame.
+        self.stop.store(true, Ordering::Relaxed);
+        match self.exited.recv_timeout(STOP_TIMEOUT) {
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                tracing::warn!("Camera didn't stop capturing within {STOP_TIMEOUT:?}");
+            }
+            _ => {
+                if let Some(thread) = self.thread.take() {
+                    let _ = thread.join();
+                }
+            }
+        }
+    }
+}
+
+/// Camera backend capturing from Media Foundation devices.
+pub struct MediaFoundationCameraBackend {
+    /// The devices found by the last `list_devices`, indexed by their id.
+    devices: Vec<DeviceSource>,
+    cameras: SlotMap<CameraHandle, OpenCamera>,
+}
+
+impl MediaFoundationCameraBackend {
+    pub fn new() -> Self {
+        Self {
+            devices: Vec::new(),
+            cameras: SlotMap::with_key(),
+        }
+    }
+}
+
+impl Default for MediaFoundationCameraBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CameraBackend for MediaFoundationCameraBackend {
+    fn list_devices(&mut self) -> Vec<CameraDevice> {
+        self.devices = match device_sources() {
+            Ok(devices) => devices,
+            Err(e) => {
+                tracing::warn!("Failed to enumerate cameras: {e}");
+                Vec::new()
+            }
+        };
+        self.devices
+            .iter()
+            .enumerate()
+            .map(|(id, device)| CameraDevice {
+                id: id as u32,
+                name: device.name.clone(),
+            })
+            .collect()
+    }
+
+    fn open(&mut self, id: u32) -> Result<CameraHandle, CameraError> {
+        let device = self.devices.get(id as usize).ok_or(CameraError::NotFound)?;
+        let symbolic_link = device.symbolic_link.clone();
+
+        let capture = Arc::new(Mutex::new(Capture::default()));
+        let stop = Arc::new(AtomicBool::new(false));
+        let (opened_sender, opened) = mpsc::channel();
+        let (exited_sender, exited) = mpsc::channel::<()>();
+        let thread = {
+            let capture = capture.clone();
+            let stop = stop.clone();
+            thread::spawn(move || {
+                let _exited_sender = exited_sender;
+                let _media_foundation = match startup() {
+                    Ok(media_foundation) => media_foundation,
+                    Err(e) => {
+                        let _ = opened_sender.send(Err(media_error(e)));
+                        return;
+                    }
+                };
+                // COM objects stay on the thread that created them, so the device is
+                // opened and read on the same thread.
+                let opened = open_reader(&symbolic_link).and_then(|reader| {
+                    let (format, stride) = reader_format(&reader).map_err(media_error)?;
+                    Ok((reader, format, stride))
+                });
+                let (reader, format, stride) = match opened {
+                    Ok(opened) => opened,
+                    Err(e) => {
+                        let _ = opened_sender.send(Err(e));
+                        return;
+                    }
+                };
+                capture.lock().expect("Camera lock poisoned").format = Some(format);
+                let _ = opened_sender.send(Ok(format));
+                read_frames(&reader, format, stride, &capture, &stop);
+            })
+        };
+
+        let format = opened
+            .recv()
+            .map_err(|_| CameraError::Other("Camera thread exited".to_string()))??;
+        Ok(self.cameras.insert(OpenCamera {
+            capture,
+            stop,
+            thread: Some(thread),
+            exited,
+            format,
+            frozen: false,
+        }))
+    }
+
+    fn close(&mut self, handle: CameraHandle) {
+        self.cameras.remove(handle);
+    }
+
+    fn format(&self, handle: CameraHandle) -> Option<CameraFormat> {
+        self.cameras.get(handle).map(|camera| camera.format)
+    }
+
+    fn supported_modes(&self, _handle: CameraHandle) -> Vec<CameraMode> {
+        // The source reader scales to any size, but the native modes aren't listed yet.
+        Vec::new()
+    }
+
+    fn set_mode(&mut self, _handle: CameraHandle, _mode: CameraMode) -> Result<(), CameraError> {
+        Err(CameraError::Other(
+            "Changing the camera mode is not supported".to_string(),
+        ))
+    }
+
+    fn set_frozen(&mut self, handle: CameraHandle, frozen: bool) {
+        if let Some(camera) = self.cameras.get_mut(handle) {
+            camera.frozen = frozen;
+        }
+    }
+
+    fn current_fps(&self, handle: CameraHandle) -> f64 {
+        match self.cameras.get(handle) {
+            Some(camera) => camera
+                .capture
+                .lock()
+                .expect("Camera lock poisoned")
+                .frame_rate
+                .per_second(Instant::now()),
+            None => 0.0,
+        }
+    }
+
+    fn poll_event(&mut self, handle: CameraHandle) -> Option<CameraEvent> {
+        let camera = self.cameras.get_mut(handle)?;
+        let format = camera
+            .capture
+            .lock()
+            .expect("Camera lock poisoned")
+            .format?;
+        if format != camera.format {
+            camera.format = format;
+            return Some(CameraEvent::FormatChanged(format));
+        }
+        None
+    }
+
+    fn next_frame(&mut self, handle: CameraHandle) -> Option<CameraFrame> {
+        let camera = self.cameras.get_mut(handle)?;
+        let frame = camera
+            .capture
+            .lock()
+            .expect("Camera lock poisoned")
+            .frame
+            .take()?;
+        if camera.frozen || frame.format != camera.format {
+            return None;
+        }
+        Some(frame)
+    }
+}
+
+/// Keeps COM and Media Foundation initialized on the current thread until it's dropped.
+///
+/// All COM objects have to be released before that, so this should be the first local
+/// of the scope using them.
+struct MediaFoundation {
+    /// Whether COM was initialized by `startup`, rather than already being initialized
+    /// differently on this thread.
+    uninitialize_com: bool,
+}
+
+impl Drop for MediaFoundation {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = MFShutdown();
+            if self.uninitialize_com {
+                CoUninitialize();
+            }
+        }
+    }
+}
+
+/// Initializes COM and Media Foundation on the current thread.
+fn startup() -> windows::core::Result<MediaFoundation> {
+    unsafe {
+        let result = CoInitializeEx(None, COINIT_MULTITHREADED);
+        // A window thread is already in a single-threaded apartment, which works just as well.
+        let uninitialize_com = result != RPC_E_CHANGED_MODE;
+        if uninitialize_com {
+            result.ok()?;
+        }
+        if let Err(e) = MFStartup(MF_VERSION, MFSTARTUP_FULL) {
+            if uninitialize_com {
+                CoUninitialize();
+            }
+            return Err(e);
+        }
+        Ok(MediaFoundation { uninitialize_com })
+    }
+}
+
+/// Lists all video capture devices.
+fn device_sources() -> windows::core::Result<Vec<DeviceSource>> {
+    let _media_foundation = startup()?;
+    let devices = enumerate_devices()?
+        .iter()
+        .filter_map(|activate| device_source(activate).ok())
+        .collect();
+    Ok(devices)
+}
+
+/// Lists the activation objects of all video capture devices.
+///
+/// COM and Media Foundation have to be initialized by `startup` first.
+fn enumerate_devices() -> windows::core::Result<Vec<IMFActivate>> {
+    unsafe {
+        let mut attributes: Option<IMFAttributes> = None;
+        MFCreateAttributes(&mut attributes, 1)?;
+        let attributes = attributes.expect("MFCreateAttributes should create attributes");
+        attributes.SetGUID(
+            &MF_DEVSOURCE_ATTRIBUTE_SOURCE_TYPE,
+            &MF_DEVSOURCE_ATTRIBUTE_SOURCE_TYPE_VIDCAP_GUID,
+        )?;
+
+        let mut sources = std::ptr::null_mut();
+        let mut count = 0;
+        MFEnumDeviceSources(&attributes, &mut sources, &mut count)?;
+        if sources.is_null() {
+            return Ok(Vec::new());
+        }
+        let devices = std::slice::from_raw_parts_mut(sources, count as usize)
+            .iter_mut()
+            .filter_map(Option::take)
+            .collect();
+        CoTaskMemFree(Some(sources as *const _));
+        Ok(devices)
+    }
+}
+
+fn device_source(activate: &IMFActivate) -> windows::core::Result<DeviceSource> {
+    Ok(DeviceSource {
+        name: allocated_string(activate, &MF_DEVSOURCE_ATTRIBUTE_FRIENDLY_NAME)?
+            .trim()
+            .to_string(),
+        symbolic_link: allocated_string(
+            activate,
+            &MF_DEVSOURCE_ATTRIBUTE_SOURCE_TYPE_VIDCAP_SYMBOLIC_LINK,
+        )?,
+    })
+}
+
+fn allocated_string(
+    activate: &IMFActivate,
+    key: &windows::core::GUID,
+) -> windows::core::Result<String> {
+    unsafe {
+        let mut value = PWSTR::null();
+        let mut length = 0;
+        activate.GetAllocatedString(key, &mut value, &mut length)?;
+        let string = value.to_string().unwrap_or_default();
+        CoTaskMemFree(Some(value.0 as *const _));
+        Ok(string)
+    }
+}
+
+/// Opens the device with the given symbolic link, reading frames as 32-bit RGB.
+fn open_reader(symbolic_link: &str) -> Result<IMFSourceReader, CameraError> {
+    let activate = enumerate_devices()
+        .map_err(media_error)?
+        .into_iter()
+        .find(|activate| {
+            device_source(activate).is_ok_and(|device| device.symbolic_link == symbolic_link)
+        })
+        .ok_or(CameraError::NotFound)?;
+
+    unsafe {
+        let source: IMFMediaSource = activate.ActivateObject().map_err(media_error)?;
+
+        // Let Media Foundation convert from whatever the device delivers.
+        let mut attributes: Option<IMFAttributes> = None;
+        MFCreateAttributes(&mut attributes, 1).map_err(media_error)?;
+        let attributes = attributes.expect("MFCreateAttributes should create attributes");
+        attributes
+            .SetUINT32(&MF_SOURCE_READER_ENABLE_VIDEO_PROCESSING, 1)
+            .map_err(media_error)?;
+        let reader =
+            MFCreateSourceReaderFromMediaSource(&source, &attributes).map_err(media_error)?;
+
+        let media_type = MFCreateMediaType().map_err(media_error)?;
+        media_type
+            .SetGUID(&MF_MT_MAJOR_TYPE, &MFMediaType_Video)
+            .map_err(media_error)?;
+        media_type
+            .SetGUID(&MF_MT_SUBTYPE, &MFVideoFormat_RGB32)
+            .map_err(media_error)?;
+        reader
+            .SetCurrentMediaType(VIDEO_STREAM, None, &media_type)
+            .map_err(media_error)?;
+        Ok(reader)
+    }
+}
+
+/// The format that a reader delivers frames in, along with the stride of their rows.
+fn reader_format(reader: &IMFSourceReader) -> windows::core::Result<(CameraFormat, i32)> {
+    unsafe {
+        let media_type = reader.GetCurrentMediaType(VIDEO_STREAM)?;
+        let size = media_type.GetUINT64(&MF_MT_FRAME_SIZE)?;
+        let width = (size >> 32) as u32;
+        let height = size as u32;
+        // RGB frames are stored bottom-up unless the stride says otherwise.
+        let stride = media_type
+            .GetUINT32(&MF_MT_DEFAULT_STRIDE)
+            .map(|stride| stride as i32)
+            .unwrap_or(-(width as i32 * 4));
+        Ok((CameraFormat::new(width, height), stride))
+    }
+}
+
+/// Reads frames into `capture` until `stop` is set or the device stops streaming.
+fn read_frames(
+    reader: &IMFSourceReader,
+    format: CameraFormat,
+    stride: i32,
+    capture: &Mutex<Capture>,
+    stop: &AtomicBool,
+) {
+    while !stop.load(Ordering::Relaxed) {
+        let mut flags = 0;
+        let mut sample = None;
+        let result = unsafe {
+            reader.ReadSample(
+                VIDEO_STREAM,
+                0,
+                None,
+                Some(&mut flags as *mut u32),
+                None,
+                Some(&mut sample as *mut _),
+            )
+        };
+        if let Err(e) = result {
+            tracing::warn!("Failed to capture camera frame: {e}");
+            return;
+        }
+        if flags & MF_SOURCE_READERF_ENDOFSTREAM.0 as u32 != 0 {
+            return;
+        }
+        let Some(sample) = sample else {
+            continue;
+        };
+
+        let rgba = unsafe {
+            let Ok(buffer) = sample.ConvertToContiguousBuffer() else {
+                continue;
+            };
+            let mut data = std::ptr::null_mut();
+            let mut length = 0;
+            if buffer
+                .Lock(&mut data, None, Some(&mut length as *mut u32))
+                .is_err()
+            {
+                continue;
+            }
+            let bgrx = std::slice::from_raw_parts(data, length as usize);
+            let rgba = bgrx_to_rgba(bgrx, format, stride);
+            let _ = buffer.Unlock();
+            rgba
+        };
+        let Some(rgba) = rgba else {
+            tracing::warn!(
+                "Camera frame is smaller than {}x{}, skipping it",
+                format.width,
+                format.height
+            );
+            continue;
+        };
+
+        let mut capture = capture.lock().expect("Camera lock poisoned");
+        capture.frame_rate.record(Instant::now());
+        capture.frame = Some(CameraFrame::rgba(format, rgba));
+    }
+}
+
+fn media_error(error: windows::core::Error) -> CameraError {
+    CameraError::Other(error.message())
+}
+
+/// Converts 32-bit RGB rows, which are stored bottom-up for a negative stride, to RGBA.
+fn bgrx_to_rgba(bgrx: &[u8], format: CameraFormat, stride: i32) -> Option<Vec<u8>> {
+    let row_length = format.width as usize * 4;
+    let row_stride = stride.unsigned_abs() as usize;
+    let height = format.height as usize;
+    if row_stride < row_length || bgrx.len() < row_stride * height.saturating_sub(1) + row_length {
+        return None;
+    }
+
+    let mut rgba = Vec::with_capacity(row_length * height);
+    for y in 0..height {
+        let row = if stride < 0 { height - 1 - y } else { y };
+        let row = &bgrx[row * row_stride..row * row_stride + row_length];
+        rgba.extend(
+            row.chunks_exact(4)
+                .flat_map(|pixel| [pixel[2], pixel[1], pixel[0], 0xFF]),
+        );
+    }
+    Some(rgba)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bottom_up_rows_are_flipped() {
+        let format = CameraFormat::new(1, 2);
+        let bgrx = [1, 2, 3, 0, 4, 5, 6, 0];
+        assert_eq!(
+            bgrx_to_rgba(&bgrx, format, -4),
+            Some(vec![6, 5, 4, 0xFF, 3, 2, 1, 0xFF])
+        );
+        assert_eq!(
+            bgrx_to_rgba(&bgrx, format, 4),
+            Some(vec![3, 2, 1, 0xFF, 6, 5, 4, 0xFF])
+        );
+    }
+
+    #[test]
+    fn short_frames_are_rejected() {
+        let format = CameraFormat::new(2, 2);
+        assert_eq!(bgrx_to_rgba(&[0; 12], format, 8), None);
+    }
+}
@@ -0,0 +1,132 @@
+use ruffle_core::backend::camera::yuyv_to_rgba;
+use ruffle_render::utils::decode_define_bits_jpeg;
+use std::sync::Once;
+
+/// The layout of the pixels in the frames that a device delivers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PixelFormat {
+    /// Packed YUV 4:2:2, with two pixels sharing their chroma in every 4 bytes.
+    Yuyv,
+
+    /// Every frame is a separate JPEG image.
+    Mjpeg,
+
+    /// 8 bits per channel RGB, without padding.
+    Rgb24,
+
+    /// Any other format, by its FourCC.
+    Other([u8; 4]),
+}
+
+impl PixelFormat {
+    pub fn from_fourcc(fourcc: [u8; 4]) -> Self {
+        match &fourcc {
+            b"YUYV" => Self::Yuyv,
+            b"MJPG" => Self::Mjpeg,
+            b"RGB3" => Self::Rgb24,
+            _ => Self::Other(fourcc),
+        }
+    }
+}
+
+/// The color of frames in a format that can't be converted: a mid gray, so that the
+/// movie can at least tell that the camera is capturing.
+const PLACEHOLDER: [u8; 4] = [0x80, 0x80, 0x80, 0xFF];
+
+static UNSUPPORTED_FORMAT: Once = Once::new();
+
+/// Converts a captured frame of the given size into RGBA pixels.
+///
+/// Returns `None` if the frame is malformed, such as when it's too small for its
+/// size. Frames in unsupported formats are replaced by a solid color.
+pub fn convert_frame(
+    format: PixelFormat,
+    bytes: &[u8],
+    width: u32,
+    height: u32,
+) -> Option<Vec<u8>> {
+    let pixels = (width * height) as usize;
+    match format {
+        PixelFormat::Yuyv => Some(yuyv_to_rgba(bytes.get(..pixels * 2)?)),
+        PixelFormat::Mjpeg => {
+            let bitmap = match decode_define_bits_jpeg(bytes, None) {
+                Ok(bitmap) => bitmap.to_rgba(),
+                Err(e) => {
+                    tracing::warn!("Failed to decode camera frame: {e}");
+                    return None;
+                }
+            };
+            if (bitmap.width(), bitmap.height()) != (width, height) {
+                tracing::warn!(
+                    "Camera frame is {}x{} instead of {width}x{height}, skipping it",
+                    bitmap.width(),
+                    bitmap.height()
+                );
+                return None;
+            }
+            Some(bitmap.data().to_vec())
+        }
+        PixelFormat::Rgb24 => Some(
+            bytes
+                .get(..pixels * 3)?
+                .chunks_exact(3)
+                .flat_map(|rgb| [rgb[0], rgb[1], rgb[2], 0xFF])
+                .collect(),
+        ),
+        PixelFormat::Other(fourcc) => {
+            UNSUPPORTED_FORMAT.call_once(|| {
+                tracing::warn!(
+                    "Unsupported camera pixel format {}",
+                    String::from_utf8_lossy(&fourcc)
+                );
+            });
+            Some(PLACEHOLDER.repeat(pixels))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn yuyv_is_converted() {
+        // Black and white, then pure red in BT.601.
+        let yuyv = [16, 128, 235, 128, 81, 90, 81, 240];
+        assert_eq!(
+            convert_frame(PixelFormat::Yuyv, &yuyv, 2, 2),
+            Some(vec![
+                0, 0, 0, 0xFF, 255, 255, 255, 0xFF, 255, 0, 0, 0xFF, 255, 0, 0, 0xFF
+            ])
+        );
+        assert_eq!(convert_frame(PixelFormat::Yuyv, &yuyv[..4], 2, 2), None);
+    }
+
+    #[test]
+    fn rgb24_gains_alpha() {
+        let rgb = [1, 2, 3, 4, 5, 6];
+        assert_eq!(
+            convert_frame(PixelFormat::Rgb24, &rgb, 2, 1),
+            Some(vec![1, 2, 3, 0xFF, 4, 5, 6, 0xFF])
+        );
+        assert_eq!(convert_frame(PixelFormat::Rgb24, &rgb, 2, 2), None);
+    }
+
+    #[test]
+    fn broken_mjpeg_is_skipped() {
+        assert_eq!(
+            convert_frame(PixelFormat::Mjpeg, &[0xFF, 0xD8, 0xFF, 0xD9], 2, 2),
+            None
+        );
+    }
+
+    #[test]
+    fn unknown_format_is_placeholder() {
+        let format = PixelFormat::from_fourcc(*b"NV12");
+        assert_eq!(format, PixelFormat::Other(*b"NV12"));
+        assert_eq!(
+            convert_frame(format, &[], 2, 1),
+            Some([PLACEHOLDER, PLACEHOLDER].concat())
+        );
+    }
+}
@@ -0,0 +1,1028 @@
+use super::frame_rate::FrameRate;
+use super::frame_ring::FrameRing;
+use super::pixel_format::{convert_frame, PixelFormat};
+use ruffle_core::backend::camera::{
+    CameraBackend, CameraDevice, CameraError, CameraEvent, CameraFormat, CameraFrame, CameraHandle,
+    CameraMode, CameraPixelFormat,
+};
+use slotmap::SlotMap;
+use std::fmt;
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use v4l::buffer::Type;
+use v4l::capability::Flags;
+use v4l::frameinterval::FrameIntervalEnum;
+use v4l::framesize::FrameSizeEnum;
+use v4l::io::mmap::Stream;
+use v4l::io::traits::CaptureStream;
+use v4l::v4l2;
+use v4l::v4l_sys::{v4l2_capability, v4l2_cropcap};
+use v4l::video::capture::Parameters;
+use v4l::video::Capture;
+use v4l::{Device, FourCC};
+
+/// How many `/dev/videoN` nodes are probed for cameras, starting at `/dev/video0`.
+///
+/// Every node is probed, even if some before it are missing, as the numbering of
+/// devices that were unplugged or that belong to virtual cameras can leave gaps.
+pub const DEFAULT_SCAN_LIMIT: usize = 10;
+
+/// How long a device may take to report its capabilities before it is skipped.
+pub const DEFAULT_PROBE_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// The number of buffers that the driver captures into.
+const BUFFER_COUNT: u32 = 4;
+
+/// The number of decoded frames that are kept for the player to pick up.
+const RING_SLOTS: usize = 3;
+
+/// How long the capture thread waits for the driver before checking whether it should stop.
+const CAPTURE_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// The pixel format that devices are asked for, as it's the one that every webcam supports.
+const YUYV: FourCC = FourCC { repr: *b"YUYV" };
+
+/// How often opening a busy device is retried before giving up.
+///
+/// A device that another application is just releasing reports `EBUSY` for a short
+/// while, so it's retried with an exponentially growing delay between attempts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OpenRetry {
+    /// The number of attempts after the initial one.
+    pub retries: u32,
+
+    /// The delay before the first retry, doubled after every attempt.
+    pub initial_delay: Duration,
+}
+
+impl Default for OpenRetry {
+    fn default() -> Self {
+        Self {
+            retries: 3,
+            initial_delay: Duration::from_millis(100),
+        }
+    }
+}
+
+/// Whether a device with the given capabilities can be used as a `Camera`.
+///
+/// Only the single-planar capture API is supported, so devices that exclusively
+/// offer `VIDEO_CAPTURE_MPLANE` (mostly hardware codecs and ISPs) are skipped. So are
+/// memory-to-memory devices like hardware codecs and scalers, which also have
+/// `VIDEO_CAPTURE` but only return the frames that they're fed.
+///
+/// Nodes that both capture and output are kept, as virtual cameras such as
+/// v4l2loopback report both.
+pub fn is_capture_device(capabilities: Flags) -> bool {
+    capabilities.contains(Flags::VIDEO_CAPTURE)
+        && !capabilities.intersects(Flags::VIDEO_M2M | Flags::VIDEO_M2M_MPLANE)
+}
+
+/// The capabilities of a single `/dev/videoN` node.
+///
+/// `capabilities` covers every node of the physical device, so the metadata node of a
+/// webcam claims `VIDEO_CAPTURE` too. Drivers that set `DEVICE_CAPS` describe the node
+/// itself in `device_caps`.
+fn node_capabilities(caps: &v4l2_capability) -> Flags {
+    let capabilities = Flags::from_bits_truncate(caps.capabilities);
+    if capabilities.contains(Flags::DEVICE_CAPS) {
+        Flags::from_bits_truncate(caps.device_caps)
+    } else {
+        capabilities
+    }
+}
+
+struct OpenCamera {
+    device: Device,
+
+    /// The format that was last reported to the player.
+    format: CameraFormat,
+
+    /// The mode that the device was switched to, if the player asked for one.
+    mode: Option<CameraMode>,
+
+    /// The pixel format that the driver settled on.
+    fourcc: FourCC,
+
+    /// The pixel format that frames should be delivered in, if the driver captures in it.
+    preferred_pixel_format: CameraPixelFormat,
+
+    capture: CaptureThread,
+}
+
+/// State shared between an open camera and its capture thread.
+struct CaptureState {
+    frames: FrameRing<CameraFrame>,
+
+    /// The format that captured frames are decoded in.
+    format: Mutex<CameraFormat>,
+
+    frame_rate: Mutex<FrameRate>,
+
+    /// Whether frames are left in the driver's queue instead of being delivered.
+    frozen: AtomicBool,
+
+    /// Whether capturing failed because the device is gone.
+    disconnected: AtomicBool,
+
+    /// Whether a frame didn't fit `format`, which means that the driver switched to
+    /// another one. The player only asks the driver for its format when this is set.
+    format_stale: AtomicBool,
+
+    stop: AtomicBool,
+}
+
+/// A thread that captures and decodes the frames of a device, so that the player
+/// never waits for the driver.
+///
+/// The thread is stopped when this is dropped, which happens when the camera is
+/// closed, or when the backend is dropped along with the player.
+struct CaptureThread {
+    state: Arc<CaptureState>,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl CaptureThread {
+    fn spawn(
+        stream: Stream<'static>,
+        format: CameraFormat,
+        pixel_format: PixelFormat,
+        preferred_pixel_format: CameraPixelFormat,
+    ) -> Self {
+        let state = Arc::new(CaptureState {
+            frames: FrameRing::new(RING_SLOTS),
+            format: Mutex::new(format),
+            frame_rate: Mutex::new(FrameRate::default()),
+            frozen: AtomicBool::new(false),
+            disconnected: AtomicBool::new(false),
+            format_stale: AtomicBool::new(false),
+            stop: AtomicBool::new(false),
+        });
+        let thread = thread::spawn({
+            let state = state.clone();
+            move || capture_frames(stream, pixel_format, preferred_pixel_format, &state)
+        });
+        Self {
+            state,
+            thread: Some(thread),
+        }
+    }
+}
+
+impl Drop for CaptureThread {
+    fn drop(&mut self) {
+        self.state.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn capture_frames(
+    mut stream: Stream<'static>,
+    pixel_format: PixelFormat,
+    preferred_pixel_format: CameraPixelFormat,
+    state: &CaptureState,
+) {
+    // Frames that are already in the preferred format are passed on without converting them.
+    let keep_yuyv =
+        pixel_format == PixelFormat::Yuyv && preferred_pixel_format == CameraPixelFormat::Yuyv;
+
+    while !state.stop.load(Ordering::Relaxed) {
+        if state.frozen.load(Ordering::Relaxed) {
+            thread::sleep(CAPTURE_TIMEOUT);
+            continue;
+        }
+
+        let buffer = match stream.next() {
+            // Drivers that don't fill in `bytesused` leave it at 0.
+            Ok((buffer, metadata)) => match metadata.bytesused as usize {
+                0 => buffer,
+                used => &buffer[..used.min(buffer.len())],
+            },
+            Err(e) if e.kind() == io::ErrorKind::TimedOut => continue,
+            Err(e) if is_disconnected(&e) => {
+                tracing::warn!("Camera was disconnected: {e}");
+                state.disconnected.store(true, Ordering::Relaxed);
+                return;
+            }
+            Err(e) => {
+                tracing::warn!("Failed to capture camera frame: {e}");
+                thread::sleep(CAPTURE_TIMEOUT);
+                continue;
+            }
+        };
+        state
+            .frame_rate
+            .lock()
+            .expect("frame rate lock poisoned")
+            .record(Instant::now());
+
+        let format = *state.format.lock().expect("format lock poisoned");
+        let CameraFormat { width, height, .. } = format;
+        let frame = if keep_yuyv {
+            buffer
+                .get(..(width * height * 2) as usize)
+                .map(|yuyv| CameraFrame {
+                    format,
+                    pixel_format: CameraPixelFormat::Yuyv,
+                    data: yuyv.to_vec(),
+                })
+        } else {
+            convert_frame(pixel_format, buffer, width, height)
+                .map(|rgba| CameraFrame::rgba(format, rgba))
+        };
+        let Some(frame) = frame else {
+            tracing::warn!("Camera frame doesn't fit {width}x{height}, skipping it");
+            state.format_stale.store(true, Ordering::Relaxed);
+            continue;
+        };
+        state.frames.push(frame);
+    }
+}
+
+enum CameraState {
+    /// The device was busy, and is being retried on a background thread.
+    Opening(mpsc::Receiver<Result<OpenCamera, CameraError>>),
+
+    Open(OpenCamera),
+
+    /// The device stopped working, which the player hasn't been told about yet.
+    Lost,
+
+    /// The device stayed busy or failed while retrying, it won't deliver any frames.
+    Failed,
+}
+
+/// Camera backend capturing from Video4Linux devices.
+///
+/// The player asks `UiBackend::request_camera_access` before opening a device, so
+/// `/dev/videoN` is never opened for a device that the user denied access to.
+pub struct V4lCameraBackend {
+    cameras: SlotMap<CameraHandle, CameraState>,
+    probe_timeout: Duration,
+    scan_limit: usize,
+    open_retry: OpenRetry,
+}
+
+impl V4lCameraBackend {
+    pub fn new() -> Self {
+        Self {
+            cameras: SlotMap::with_key(),
+            probe_timeout: DEFAULT_PROBE_TIMEOUT,
+            scan_limit: DEFAULT_SCAN_LIMIT,
+            open_retry: OpenRetry::default(),
+        }
+    }
+
+    /// Sets how long a device may take to respond while enumerating cameras.
+    pub fn with_probe_timeout(mut self, probe_timeout: Duration) -> Self {
+        self.probe_timeout = probe_timeout;
+        self
+    }
+
+    /// Sets how many `/dev/videoN` nodes are probed while enumerating cameras.
+    pub fn with_scan_limit(mut self, scan_limit: usize) -> Self {
+        self.scan_limit = scan_limit;
+        self
+    }
+
+    /// Sets how opening a busy device is retried.
+    pub fn with_open_retry(mut self, open_retry: OpenRetry) -> Self {
+        self.open_retry = open_retry;
+        self
+    }
+
+    fn open_camera(&mut self, handle: CameraHandle) -> Option<&mut OpenCamera> {
+        let state = self.cameras.get_mut(handle)?;
+        if let CameraState::Opening(receiver) = state {
+            match receiver.try_recv() {
+                Ok(Ok(camera)) => *state = CameraState::Open(camera),
+                Ok(Err(e)) => {
+                    tracing::warn!("Failed to open busy camera: {e}");
+                    *state = CameraState::Failed;
+                }
+                Err(mpsc::TryRecvError::Empty) => {}
+                Err(mpsc::TryRecvError::Disconnected) => *state = CameraState::Failed,
+            }
+        }
+        match state {
+            CameraState::Open(camera) => Some(camera),
+            _ => None,
+        }
+    }
+}
+
+impl Default for V4lCameraBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CameraBackend for V4lCameraBackend {
+    fn list_devices(&mut self) -> Vec<CameraDevice> {
+        probe_devices(0..self.scan_limit, self.probe_timeout, probe_device)
+    }
+
+    fn open(&mut self, id: u32) -> Result<CameraHandle, CameraError> {
+        let state = match Device::new(id as usize)
+            .map_err(device_error)
+            .and_then(|device| start_capture(device, None, CameraPixelFormat::Rgba))
+        {
+            Ok(camera) => CameraState::Open(camera),
+            // Retrying sleeps between attempts, which must not stall the player.
+            Err(CameraError::Busy) if self.open_retry.retries > 0 => {
+                let retry = self.open_retry;
+                let (sender, receiver) = mpsc::channel();
+                thread::spawn(move || {
+                    let _ = sender.send(retry_busy(retry, || {
+                        let device = Device::new(id as usize).map_err(device_error)?;
+                        start_capture(device, None, CameraPixelFormat::Rgba)
+                    }));
+                });
+                CameraState::Opening(receiver)
+            }
+            Err(e) => return Err(e),
+        };
+        Ok(self.cameras.insert(state))
+    }
+
+    fn close(&mut self, handle: CameraHandle) {
+        self.cameras.remove(handle);
+    }
+
+    fn format(&self, handle: CameraHandle) -> Option<CameraFormat> {
+        match self.cameras.get(handle)? {
+            CameraState::Open(camera) => Some(camera.format),
+            _ => None,
+        }
+    }
+
+    fn supported_modes(&self, handle: CameraHandle) -> Vec<CameraMode> {
+        let Some(CameraState::Open(camera)) = self.cameras.get(handle) else {
+            return Vec::new();
+        };
+        let Ok(sizes) = camera.device.enum_framesizes(camera.fourcc) else {
+            return Vec::new();
+        };
+
+        // Only discrete sizes and intervals are listed, which is what webcams report.
+        let mut modes = Vec::new();
+        for size in sizes {
+            let FrameSizeEnum::Discrete(size) = size.size else {
+                continue;
+            };
+            let Ok(intervals) =
+                camera
+                    .device
+                    .enum_frameintervals(camera.fourcc, size.width, size.height)
+            else {
+                continue;
+            };
+            for interval in intervals {
+                if let FrameIntervalEnum::Discrete(interval) = interval.interval {
+                    if interval.numerator != 0 {
+                        modes.push(CameraMode {
+                            width: size.width,
+                            height: size.height,
+                            fps: interval.denominator as f64 / interval.numerator as f64,
+                        });
+                    }
+                }
+            }
+        }
+        modes
+    }
+
+    fn set_mode(&mut self, handle: CameraHandle, mode: CameraMode) -> Result<(), CameraError> {
+        let state = self.cameras.get_mut(handle).ok_or(CameraError::NotFound)?;
+        if !matches!(state, CameraState::Open(_)) {
+            return Err(CameraError::Other("Camera isn't open yet".to_string()));
+        }
+        let CameraState::Open(camera) = std::mem::replace(state, CameraState::Failed) else {
+            unreachable!();
+        };
+
+        // The format can't be changed while buffers are allocated, so stop streaming first.
+        let OpenCamera {
+            device,
+            mode: old_mode,
+            capture,
+            preferred_pixel_format,
+            ..
+        } = camera;
+        let frozen = capture.state.frozen.load(Ordering::Relaxed);
+        drop(capture);
+        let (camera, result) = match restart_capture(device, Some(mode), preferred_pixel_format) {
+            Ok(camera) => (camera, Ok(())),
+            // Keep capturing in the previous mode, rather than losing the camera.
+            Err((device, e)) => match restart_capture(device, old_mode, preferred_pixel_format) {
+                Ok(camera) => (camera, Err(e)),
+                Err((_, restart_error)) => {
+                    tracing::warn!(
+                        "Failed to restart camera after switching modes: {restart_error}"
+                    );
+                    *state = CameraState::Lost;
+                    return Err(e);
+                }
+            },
+        };
+        camera.capture.state.frozen.store(frozen, Ordering::Relaxed);
+        *state = CameraState::Open(camera);
+        result
+    }
+
+    fn set_preferred_pixel_format(
+        &mut self,
+        handle: CameraHandle,
+        pixel_format: CameraPixelFormat,
+    ) {
+        if let Some(camera) = self.open_camera(handle) {
+            camera.preferred_pixel_format = pixel_format;
+        }
+    }
+
+    fn set_frozen(&mut self, handle: CameraHandle, frozen: bool) {
+        if let Some(camera) = self.open_camera(handle) {
+            camera.capture.state.frozen.store(frozen, Ordering::Relaxed);
+        }
+    }
+
+    fn current_fps(&self, handle: CameraHandle) -> f64 {
+        match self.cameras.get(handle) {
+            Some(CameraState::Open(camera)) => camera
+                .capture
+                .state
+                .frame_rate
+                .lock()
+                .expect("frame rate lock poisoned")
+                .per_second(Instant::now()),
+            _ => 0.0,
+        }
+    }
+
+    fn poll_event(&mut self, handle: CameraHandle) -> Option<CameraEvent> {
+        if let CameraState::Lost = self.cameras.get(handle)? {
+            self.cameras[handle] = CameraState::Failed;
+            return Some(CameraEvent::Disconnected);
+        }
+        let just_opened = matches!(self.cameras.get(handle)?, CameraState::Opening(_));
+        let camera = self.open_camera(handle)?;
+        if just_opened {
+            return Some(CameraEvent::FormatChanged(camera.format));
+        }
+        if camera.capture.state.disconnected.load(Ordering::Relaxed) {
+            self.cameras[handle] = CameraState::Failed;
+            return Some(CameraEvent::Disconnected);
+        }
+
+        if !camera
+            .capture
+            .state
+            .format_stale
+            .swap(false, Ordering::Relaxed)
+        {
+            return None;
+        }
+        let format = camera.device.format().ok()?;
+        let format = CameraFormat {
+            width: format.width,
+            height: format.height,
+            pixel_aspect_ratio: pixel_aspect_ratio(&camera.device),
+        };
+        if format != camera.format {
+            camera.format = format;
+            *camera
+                .capture
+                .state
+                .format
+                .lock()
+                .expect("format lock poisoned") = format;
+            return Some(CameraEvent::FormatChanged(format));
+        }
+        None
+    }
+
+    fn next_frame(&mut self, handle: CameraHandle) -> Option<CameraFrame> {
+        let camera = self.open_camera(handle)?;
+        if camera.capture.state.frozen.load(Ordering::Relaxed) {
+            return None;
+        }
+
+        // Frames that queued up since the last tick are skipped, only the newest one matters.
+        camera.capture.state.frames.take_latest()
+    }
+}
+
+/// Starts streaming from a device, optionally switching it to another mode first.
+fn start_capture(
+    device: Device,
+    mode: Option<CameraMode>,
+    preferred_pixel_format: CameraPixelFormat,
+) -> Result<OpenCamera, CameraError> {
+    restart_capture(device, mode, preferred_pixel_format).map_err(|(_, e)| e)
+}
+
+/// Like `start_capture`, but hands the device back on failure, so that capturing can
+/// be restarted in another mode.
+fn restart_capture(
+    device: Device,
+    mode: Option<CameraMode>,
+    preferred_pixel_format: CameraPixelFormat,
+) -> Result<OpenCamera, (Device, CameraError)> {
+    let (fourcc, format, stream) = match configure_capture(&device, mode) {
+        Ok(capture) => capture,
+        Err(e) => return Err((device, e)),
+    };
+    Ok(OpenCamera {
+        device,
+        format,
+        mode,
+        fourcc,
+        preferred_pixel_format,
+        capture: CaptureThread::spawn(
+            stream,
+            format,
+            PixelFormat::from_fourcc(fourcc.repr),
+            preferred_pixel_format,
+        ),
+    })
+}
+
+/// Switches a device to a mode and allocates the buffers to stream from it.
+fn configure_capture(
+    device: &Device,
+    mode: Option<CameraMode>,
+) -> Result<(FourCC, CameraFormat, Stream<'static>), CameraError> {
+    let mut format = device.format().map_err(device_error)?;
+    format.fourcc = YUYV;
+    if let Some(mode) = mode {
+        format.width = mode.width;
+        format.height = mode.height;
+    }
+    // Drivers that can't deliver YUYV pick another format, which is converted if possible.
+    let format = device.set_format(&format).map_err(device_error)?;
+    if let Some(mode) = mode {
+        device
+            .set_params(&Parameters::with_fps(mode.fps.round() as u32))
+            .map_err(device_error)?;
+    }
+
+    let mut stream =
+        Stream::with_buffers(device, Type::VideoCapture, BUFFER_COUNT).map_err(device_error)?;
+    // The capture thread regularly stops waiting for the driver, to see whether it should stop.
+    stream.set_timeout(CAPTURE_TIMEOUT);
+
+    let camera_format = CameraFormat {
+        width: format.width,
+        height: format.height,
+        pixel_aspect_ratio: pixel_aspect_ratio(device),
+    };
+    Ok((format.fourcc, camera_format, stream))
+}
+
+/// Calls `open` until it stops failing with `CameraError::Busy`, sleeping between attempts.
+fn retry_busy<T>(
+    retry: OpenRetry,
+    mut open: impl FnMut() -> Result<T, CameraError>,
+) -> Result<T, CameraError> {
+    let mut delay = retry.initial_delay;
+    let mut result = open();
+    for _ in 0..retry.retries {
+        if !matches!(result, Err(CameraError::Busy)) {
+            break;
+        }
+        thread::sleep(delay);
+        delay = delay.saturating_mul(2);
+        result = open();
+    }
+    result
+}
+
+/// Queries the capabilities of a device with a raw `VIDIOC_QUERYCAP`.
+///
+/// `Device::query_caps` panics on card names that aren't valid UTF-8, which some
+/// cheap webcams report, so the name is decoded by `CameraDevice::from_raw_name` instead.
+fn query_caps(device: &Device) -> io::Result<v4l2_capability> {
+    let mut caps: v4l2_capability = unsafe { std::mem::zeroed() };
+    unsafe {
+        v4l2::ioctl(
+            device.handle().fd(),
+            v4l2::vidioc::VIDIOC_QUERYCAP,
+            &mut caps as *mut _ as *mut std::os::raw::c_void,
+        )
+    }?;
+    Ok(caps)
+}
+
+fn probe_device(index: usize) -> Option<CameraDevice> {
+    let caps = query_caps(&Device::new(index).ok()?).ok()?;
+    is_capture_device(node_capabilities(&caps))
+        .then(|| CameraDevice::from_raw_name(index as u32, &caps.card))
+}
+
+/// Whether a `/dev/videoN` node is listed as a camera, and why.
+#[derive(Debug)]
+pub enum DeviceStatus {
+    /// The node couldn't be opened or didn't answer `VIDIOC_QUERYCAP`.
+    Unavailable(io::Error),
+
+    /// The node doesn't support single-planar video capture, or is a memory-to-memory
+    /// device, so it isn't listed.
+    NotCapture,
+
+    /// The node is listed, along with the result of trying to start capturing from it.
+    Capture(Result<CameraFormat, CameraError>),
+}
+
+/// Diagnostic information about a `/dev/videoN` node, meant to be attached to bug reports
+/// about cameras that aren't detected.
+#[derive(Debug)]
+pub struct DeviceReport {
+    pub index: usize,
+    pub card: String,
+    pub driver: String,
+    pub bus_info: String,
+    pub capabilities: Flags,
+    pub status: DeviceStatus,
+}
+
+impl fmt::Display for DeviceReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "/dev/video{}: ", self.index)?;
+        if let DeviceStatus::Unavailable(e) = &self.status {
+            return write!(f, "unavailable ({e})");
+        }
+        writeln!(
+            f,
+            "{:?} (driver {:?}, bus {:?})",
+            self.card, self.driver, self.bus_info
+        )?;
+        writeln!(f, "  capabilities: {:?}", self.capabilities)?;
+        match &self.status {
+            DeviceStatus::Unavailable(_) => unreachable!(),
+            DeviceStatus::NotCapture
+                if self
+                    .capabilities
+                    .intersects(Flags::VIDEO_M2M | Flags::VIDEO_M2M_MPLANE) =>
+            {
+                write!(f, "  excluded: memory-to-memory device")
+            }
+            DeviceStatus::NotCapture => write!(
+                f,
+                "  excluded: no single-planar VIDEO_CAPTURE support{}",
+                if self.capabilities.contains(Flags::VIDEO_CAPTURE_MPLANE) {
+                    " (only VIDEO_CAPTURE_MPLANE)"
+                } else {
+                    ""
+                }
+            ),
+            DeviceStatus::Capture(Ok(format)) => write!(
+                f,
+                "  included: captures at {}x{}, pixel aspect ratio {}:{}",
+                format.width,
+                format.height,
+                format.pixel_aspect_ratio.0,
+                format.pixel_aspect_ratio.1
+            ),
+            DeviceStatus::Capture(Err(e)) => write!(f, "  included, but failed to open: {e}"),
+        }
+    }
+}
+
+/// Reports on every `/dev/videoN` node that is probed for cameras.
+///
+/// This briefly opens every camera that would be listed, so it shouldn't be called
+/// while a movie is capturing.
+pub fn diagnose_devices(scan_limit: usize) -> Vec<DeviceReport> {
+    (0..scan_limit).map(diagnose_device).collect()
+}
+
+fn diagnose_device(index: usize) -> DeviceReport {
+    let mut report = DeviceReport {
+        index,
+        card: String::new(),
+        driver: String::new(),
+        bus_info: String::new(),
+        capabilities: Flags::empty(),
+        status: DeviceStatus::NotCapture,
+    };
+    let caps = match Device::new(index).and_then(|device| query_caps(&device)) {
+        Ok(caps) => caps,
+        Err(e) => {
+            report.status = DeviceStatus::Unavailable(e);
+            return report;
+        }
+    };
+
+    report.card = CameraDevice::from_raw_name(index as u32, &caps.card).name;
+    report.driver = CameraDevice::from_raw_name(index as u32, &caps.driver).name;
+    report.bus_info = CameraDevice::from_raw_name(index as u32, &caps.bus_info).name;
+    report.capabilities = node_capabilities(&caps);
+    if is_capture_device(report.capabilities) {
+        report.status = DeviceStatus::Capture(
+            Device::new(index)
+                .map_err(device_error)
+                .and_then(|device| start_capture(device, None, CameraPixelFormat::Rgba))
+                .map(|camera| camera.format),
+        );
+    }
+    report
+}
+
+/// Probes all devices in parallel on background threads.
+///
+/// Some drivers never return from `open` or `VIDIOC_QUERYCAP`, so devices that don't
+/// respond before the timeout are skipped. Their threads are left behind, as there's
+/// no way to interrupt a blocked syscall.
+fn probe_devices(
+    indices: impl IntoIterator<Item = usize>,
+    timeout: Duration,
+    probe: fn(usize) -> Option<CameraDevice>,
+) -> Vec<CameraDevice> {
+    let pending: Vec<_> = indices
+        .into_iter()
+        .map(|index| {
+            let (sender, receiver) = mpsc::channel();
+            thread::spawn(move || {
+                let _ = sender.send(probe(index));
+            });
+            (index, receiver)
+        })
+        .collect();
+
+    let deadline = Instant::now() + timeout;
+    pending
+        .into_iter()
+        .filter_map(|(index, receiver)| {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match receiver.recv_timeout(remaining) {
+                Ok(device) => device,
+                Err(mpsc::RecvTimeoutError::Timeout) => {
+                    tracing::warn!(
+                        "Camera /dev/video{index} didn't respond within {timeout:?}, skipping it"
+                    );
+                    None
+                }
+                Err(mpsc::RecvTimeoutError::Disconnected) => None,
+            }
+        })
+        .collect()
+}
+
+/// Queries the shape of the pixels of the current format, falling back to square pixels
+/// for drivers that don't support `VIDIOC_CROPCAP`.
+fn pixel_aspect_ratio(device: &Device) -> (u32, u32) {
+    let mut cropcap = v4l2_cropcap {
+        type_: Type::VideoCapture as u32,
+        ..unsafe { std::mem::zeroed() }
+    };
+    let result = unsafe {
+        v4l2::ioctl(
+            device.handle().fd(),
+            v4l2::vidioc::VIDIOC_CROPCAP,
+            &mut cropcap as *mut _ as *mut std::os::raw::c_void,
+        )
+    };
+    match result {
+        Ok(()) if cropcap.pixelaspect.numerator != 0 && cropcap.pixelaspect.denominator != 0 => (
+            cropcap.pixelaspect.numerator,
+            cropcap.pixelaspect.denominator,
+        ),
+        _ => (1, 1),
+    }
+}
+
+/// `errno` values that `io::ErrorKind` doesn't have a kind for.
+const EIO: i32 = 5;
+const ENXIO: i32 = 6;
+const ENODEV: i32 = 19;
+
+/// Classifies the error of a V4L2 call.
+///
+/// The `v4l` crate reports failed ioctls as plain `io::Error`s carrying their `errno`.
+/// A device that was unplugged fails with `ENODEV` or `ENXIO` though, which don't map
+/// to any `io::ErrorKind`, so they're treated as missing here.
+fn classify_v4l_error(error: &io::Error) -> io::ErrorKind {
+    match error.raw_os_error() {
+        Some(ENXIO | ENODEV) => io::ErrorKind::NotFound,
+        _ => error.kind(),
+    }
+}
+
+/// Whether capturing from a device failed because it was unplugged.
+///
+/// Drivers fail with `EIO` on an ongoing capture, rather than with `ENODEV`.
+fn is_disconnected(error: &io::Error) -> bool {
+    classify_v4l_error(error) == io::ErrorKind::NotFound || error.raw_os_error() == Some(EIO)
+}
+
+fn device_error(error: io::Error) -> CameraError {
+    match classify_v4l_error(&error) {
+        io::ErrorKind::NotFound => CameraError::NotFound,
+        io::ErrorKind::PermissionDenied => CameraError::PermissionDenied,
+        io::ErrorKind::ResourceBusy => CameraError::Busy,
+        _ => CameraError::Other(error.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn probe_with_stuck_device(index: usize) -> Option<CameraDevice> {
+        if index == 1 {
+            loop {
+                thread::park();
+            }
+        }
+        Some(CameraDevice {
+            id: index as u32,
+            name: format!("Camera {index}"),
+        })
+    }
+
+    #[test]
+    fn probe_skips_unresponsive_device() {
+        let start = Instant::now();
+        let devices = probe_devices(0..3, Duration::from_millis(100), probe_with_stuck_device);
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert_eq!(
+            devices.iter().map(|device| device.id).collect::<Vec<_>>(),
+            vec![0, 2]
+        );
+    }
+
+    #[test]
+    fn busy_device_is_retried() {
+        let retry = OpenRetry {
+            retries: 3,
+            initial_delay: Duration::from_millis(1),
+        };
+        let mut attempts = 0;
+        let result = retry_busy(retry, || {
+            attempts += 1;
+            if attempts < 2 {
+                Err(CameraError::Busy)
+            } else {
+                Ok(attempts)
+            }
+        });
+        assert!(matches!(result, Ok(2)));
+    }
+
+    #[test]
+    fn busy_device_gives_up_after_retries() {
+        let retry = OpenRetry {
+            retries: 2,
+            initial_delay: Duration::from_millis(1),
+        };
+        let mut attempts = 0;
+        let result = retry_busy(retry, || -> Result<(), _> {
+            attempts += 1;
+            Err(CameraError::Busy)
+        });
+        assert!(matches!(result, Err(CameraError::Busy)));
+        assert_eq!(attempts, 3);
+    }
+
+    #[test]
+    fn other_errors_are_not_retried() {
+        let mut attempts = 0;
+        let result = retry_busy(OpenRetry::default(), || -> Result<(), _> {
+            attempts += 1;
+            Err(CameraError::NotFound)
+        });
+        assert!(matches!(result, Err(CameraError::NotFound)));
+        assert_eq!(attempts, 1);
+    }
+
+    #[test]
+    fn card_name_with_invalid_utf8_is_kept() {
+        let mut card = [0u8; 32];
+        card[..12].copy_from_slice(b"USB \xffCamera ");
+        let device = CameraDevice::from_raw_name(3, &card);
+        assert_eq!(device.id, 3);
+        assert_eq!(device.name, "USB \u{FFFD}Camera");
+    }
+
+    #[test]
+    fn report_explains_multi_planar_exclusion() {
+        let report = DeviceReport {
+            index: 2,
+            card: "rkisp1_mainpath".to_string(),
+            driver: "rkisp1".to_string(),
+            bus_info: "platform:rkisp1".to_string(),
+            capabilities: Flags::VIDEO_CAPTURE_MPLANE | Flags::STREAMING,
+            status: DeviceStatus::NotCapture,
+        };
+        let report = report.to_string();
+        assert!(report.starts_with("/dev/video2: \"rkisp1_mainpath\""));
+        assert!(report.contains("excluded"));
+        assert!(report.contains("only VIDEO_CAPTURE_MPLANE"));
+    }
+
+    #[test]
+    fn video_capture_is_accepted() {
+        assert!(is_capture_device(Flags::VIDEO_CAPTURE));
+        assert!(is_capture_device(
+            Flags::VIDEO_CAPTURE | Flags::STREAMING | Flags::EXT_PIX_FORMAT
+        ));
+    }
+
+    #[test]
+    fn missing_video_capture_is_rejected() {
+        assert!(!is_capture_device(Flags::empty()));
+        assert!(!is_capture_device(Flags::VIDEO_OUTPUT | Flags::STREAMING));
+        assert!(!is_capture_device(Flags::META_CAPTURE));
+    }
+
+    #[test]
+    fn multi_planar_capture_is_rejected() {
+        assert!(!is_capture_device(Flags::VIDEO_CAPTURE_MPLANE));
+        assert!(!is_capture_device(
+            Flags::VIDEO_CAPTURE_MPLANE | Flags::VIDEO_M2M_MPLANE | Flags::STREAMING
+        ));
+        assert!(is_capture_device(
+            Flags::VIDEO_CAPTURE | Flags::VIDEO_CAPTURE_MPLANE
+        ));
+    }
+
+    /// The answer to `VIDIOC_QUERYCAP` of a node, as reported by a driver that sets
+    /// `DEVICE_CAPS`.
+    fn node_caps(device: Flags, node: Flags) -> v4l2_capability {
+        let mut caps: v4l2_capability = unsafe { std::mem::zeroed() };
+        caps.capabilities = (device | Flags::DEVICE_CAPS).bits();
+        caps.device_caps = node.bits();
+        caps
+    }
+
+    #[test]
+    fn mixed_nodes_list_only_cameras() {
+        let webcam = Flags::VIDEO_CAPTURE | Flags::META_CAPTURE | Flags::STREAMING;
+        let codec = Flags::VIDEO_M2M | Flags::STREAMING;
+        let nodes = [
+            // A UVC webcam, with its image and metadata nodes.
+            node_caps(webcam, Flags::VIDEO_CAPTURE | Flags::STREAMING),
+            node_caps(webcam, Flags::META_CAPTURE | Flags::STREAMING),
+            // A hardware codec, which has both capture and output queues.
+            node_caps(codec, codec | Flags::VIDEO_CAPTURE | Flags::VIDEO_OUTPUT),
+            // A video output, like a TV encoder.
+            node_caps(Flags::VIDEO_OUTPUT, Flags::VIDEO_OUTPUT | Flags::STREAMING),
+            // A virtual camera, which is fed by another application.
+            node_caps(
+                Flags::VIDEO_CAPTURE | Flags::VIDEO_OUTPUT,
+                Flags::VIDEO_CAPTURE | Flags::VIDEO_OUTPUT | Flags::STREAMING,
+            ),
+        ];
+        let listed: Vec<_> = nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, caps)| is_capture_device(node_capabilities(caps)))
+            .map(|(index, _)| index)
+            .collect();
+        assert_eq!(listed, vec![0, 4]);
+
+        // Without `DEVICE_CAPS`, the capabilities of the whole device are all there is.
+        let mut caps = node_caps(webcam, Flags::empty());
+        caps.capabilities = webcam.bits();
+        assert_eq!(node_capabilities(&caps), webcam);
+    }
+
+    #[test]
+    fn report_explains_m2m_exclusion() {
+        let report = DeviceReport {
+            index: 4,
+            card: "hantro-vpu".to_string(),
+            driver: "hantro-vpu".to_string(),
+            bus_info: "platform:hantro-vpu".to_string(),
+            capabilities: Flags::VIDEO_M2M | Flags::STREAMING,
+            status: DeviceStatus::NotCapture,
+        };
+        assert!(report
+            .to_string()
+            .contains("excluded: memory-to-memory device"));
+    }
+
+    #[test]
+    fn unplugged_device_is_not_found() {
+        for errno in [ENXIO, ENODEV] {
+            let error = io::Error::from_raw_os_error(errno);
+            assert_eq!(classify_v4l_error(&error), io::ErrorKind::NotFound);
+            assert!(matches!(device_error(error), CameraError::NotFound));
+        }
+
+        assert!(is_disconnected(&io::Error::from_raw_os_error(EIO)));
+        assert!(is_disconnected(&io::Error::from_raw_os_error(ENODEV)));
+        assert!(!is_disconnected(&io::Error::from(io::ErrorKind::TimedOut)));
+
+        // EACCES and EBUSY already have a kind of their own.
+        let error = io::Error::from_raw_os_error(13);
+        assert!(matches!(device_error(error), CameraError::PermissionDenied));
+        let error = io::Error::from_raw_os_error(16);
+        assert!(matches!(device_error(error), CameraError::Busy));
+    }
+}
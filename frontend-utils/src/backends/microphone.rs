@@ -0,0 +1,5 @@
+#[cfg(feature = "cpal")]
+mod cpal_input;
+
+#[cfg(feature = "cpal")]
+pub use cpal_input::CpalMicrophoneBackend;
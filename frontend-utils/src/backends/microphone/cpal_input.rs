@@ -0,0 +1,197 @@
+use crate::backends::audio_devices::{input_devices, list_input_devices};
+use cpal::traits::{DeviceTrait, StreamTrait};
+use cpal::{Sample, SampleFormat, SizedSample};
+use ruffle_core::backend::microphone::{
+    MicrophoneBackend, MicrophoneDevice, MicrophoneError, MicrophoneHandle,
+};
+use slotmap::SlotMap;
+use std::sync::{Arc, Mutex};
+
+/// How many seconds of audio are kept for the player to pick up, older samples are dropped.
+const MAX_BUFFERED_SECONDS: usize = 2;
+
+struct OpenMicrophone {
+    // Capturing stops when the stream is dropped.
+    _stream: cpal::Stream,
+
+    samples: Arc<Mutex<Vec<f32>>>,
+}
+
+/// Microphone backend capturing from the input devices of cpal.
+///
+/// cpal delivers the captured audio on its own thread, where it's mixed down to mono and
+/// resampled to the rate of the `Microphone`.
+pub struct CpalMicrophoneBackend {
+    host: cpal::Host,
+    microphones: SlotMap<MicrophoneHandle, OpenMicrophone>,
+}
+
+impl CpalMicrophoneBackend {
+    pub fn new() -> Self {
+        Self {
+            host: cpal::default_host(),
+            microphones: SlotMap::with_key(),
+        }
+    }
+}
+
+impl Default for CpalMicrophoneBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MicrophoneBackend for CpalMicrophoneBackend {
+    fn list_devices(&mut self) -> Vec<MicrophoneDevice> {
+        list_input_devices(&self.host)
+            .into_iter()
+            .map(|(id, name)| MicrophoneDevice { id, name })
+            .collect()
+    }
+
+    fn open(&mut self, id: u32, sample_rate: u32) -> Result<MicrophoneHandle, MicrophoneError> {
+        let device = input_devices(&self.host)
+            .into_iter()
+            .nth(id as usize)
+            .ok_or(MicrophoneError::NotFound)?;
+        let config = device
+            .default_input_config()
+            .map_err(|e| MicrophoneError::Other(e.to_string()))?;
+        let sample_format = config.sample_format();
+        let config = cpal::StreamConfig::from(config);
+
+        let samples = Arc::new(Mutex::new(Vec::new()));
+        let capture = Capture {
+            samples: samples.clone(),
+            channels: config.channels.into(),
+            resampler: Resampler::new(config.sample_rate.0, sample_rate),
+            max_buffered: sample_rate as usize * MAX_BUFFERED_SECONDS,
+        };
+        let stream = match sample_format {
+            SampleFormat::F32 => build_stream::<f32>(&device, &config, capture),
+            SampleFormat::I16 => build_stream::<i16>(&device, &config, capture),
+            SampleFormat::U16 => build_stream::<u16>(&device, &config, capture),
+            _ => {
+                return Err(MicrophoneError::Other(format!(
+                    "Unsupported sample format {sample_format:?}"
+                )))
+            }
+        }
+        .map_err(|e| MicrophoneError::Other(e.to_string()))?;
+        stream
+            .play()
+            .map_err(|e| MicrophoneError::Other(e.to_string()))?;
+
+        Ok(self.microphones.insert(OpenMicrophone {
+            _stream: stream,
+            samples,
+        }))
+    }
+
+    fn close(&mut self, handle: MicrophoneHandle) {
+        self.microphones.remove(handle);
+    }
+
+    fn take_samples(&mut self, handle: MicrophoneHandle) -> Vec<f32> {
+        match self.microphones.get(handle) {
+            Some(microphone) => {
+                std::mem::take(&mut *microphone.samples.lock().expect("samples lock poisoned"))
+            }
+            None => Vec::new(),
+        }
+    }
+}
+
+/// The state of the capture callback.
+struct Capture {
+    samples: Arc<Mutex<Vec<f32>>>,
+    channels: usize,
+    resampler: Resampler,
+    max_buffered: usize,
+}
+
+fn build_stream<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    mut capture: Capture,
+) -> Result<cpal::Stream, cpal::BuildStreamError>
+where
+    T: SizedSample,
+    f32: cpal::FromSample<T>,
+{
+    device.build_input_stream(
+        config,
+        move |data: &[T], _| {
+            let mut samples = capture.samples.lock().expect("samples lock poisoned");
+            for frame in data.chunks_exact(capture.channels) {
+                let mono = frame
+                    .iter()
+                    .map(|sample| sample.to_sample::<f32>())
+                    .sum::<f32>()
+                    / capture.channels as f32;
+                capture.resampler.push(mono, &mut samples);
+            }
+            if samples.len() > capture.max_buffered {
+                let excess = samples.len() - capture.max_buffered;
+                samples.drain(..excess);
+            }
+        },
+        |e| tracing::error!("Microphone stream error: {e}"),
+        None,
+    )
+}
+
+/// Converts audio to another sample rate by picking the nearest sample.
+///
+/// This doesn't filter anything, which is good enough for measuring the loudness of
+/// voice recordings.
+struct Resampler {
+    /// The number of input samples per output sample.
+    step: f64,
+
+    /// How far the next output sample is from the current input sample.
+    phase: f64,
+}
+
+impl Resampler {
+    fn new(input_rate: u32, output_rate: u32) -> Self {
+        Self {
+            step: f64::from(input_rate) / f64::from(output_rate.max(1)),
+            phase: 0.0,
+        }
+    }
+
+    fn push(&mut self, sample: f32, output: &mut Vec<f32>) {
+        while self.phase < 1.0 {
+            output.push(sample);
+            self.phase += self.step;
+        }
+        self.phase -= 1.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Resampler;
+
+    fn resample(input_rate: u32, output_rate: u32, input: &[f32]) -> Vec<f32> {
+        let mut resampler = Resampler::new(input_rate, output_rate);
+        let mut output = Vec::new();
+        for sample in input {
+            resampler.push(*sample, &mut output);
+        }
+        output
+    }
+
+    #[test]
+    fn resampler_changes_rate() {
+        let input: Vec<f32> = (0..8).map(|i| i as f32).collect();
+        assert_eq!(resample(44100, 44100, &input), input);
+        assert_eq!(resample(44100, 22050, &input), [0.0, 2.0, 4.0, 6.0]);
+        assert_eq!(
+            resample(8000, 16000, &input[..3]),
+            [0.0, 0.0, 1.0, 1.0, 2.0, 2.0]
+        );
+        assert_eq!(resample(48000, 8000, &input).len(), 2);
+    }
+}
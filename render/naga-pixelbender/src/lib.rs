@@ -490,7 +490,12 @@ impl ShaderBuilder<'_> {
                         PixelBenderTypeOpcode::TInt
                         | PixelBenderTypeOpcode::TInt2
                         | PixelBenderTypeOpcode::TInt3
-                        | PixelBenderTypeOpcode::TInt4 => {
+                        | PixelBenderTypeOpcode::TInt4
+                        // Bools are stored in int registers.
+                        | PixelBenderTypeOpcode::TBool
+                        | PixelBenderTypeOpcode::TBool2
+                        | PixelBenderTypeOpcode::TBool3
+                        | PixelBenderTypeOpcode::TBool4 => {
                             num_vec4is += 1;
                             (int_offset, ParamKind::Int)
                         }
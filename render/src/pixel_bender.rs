@@ -12,6 +12,7 @@ use std::{
     io::Read,
     sync::Arc,
 };
+use thiserror::Error;
 
 use crate::{backend::RawTexture, bitmap::BitmapHandle};
 
@@ -47,6 +48,86 @@ pub enum PixelBenderType {
     TInt3(i16, i16, i16) = 0xA,
     TInt4(i16, i16, i16, i16) = 0xB,
     TString(String) = 0xC,
+    TBool(bool) = 0xD,
+    TBool2(bool, bool) = 0xE,
+    TBool3(bool, bool, bool) = 0xF,
+    TBool4(bool, bool, bool, bool) = 0x10,
+}
+
+impl PixelBenderType {
+    /// The number of components in this value, e.g. 2 for `TFloat2` and 9 for `TFloat3x3`.
+    ///
+    /// A string counts as a single component, unless it's empty.
+    pub fn len(&self) -> usize {
+        match self {
+            PixelBenderType::TFloat(_) | PixelBenderType::TInt(_) | PixelBenderType::TBool(_) => 1,
+            PixelBenderType::TFloat2(..)
+            | PixelBenderType::TInt2(..)
+            | PixelBenderType::TBool2(..) => 2,
+            PixelBenderType::TFloat3(..)
+            | PixelBenderType::TInt3(..)
+            | PixelBenderType::TBool3(..) => 3,
+            PixelBenderType::TFloat4(..)
+            | PixelBenderType::TInt4(..)
+            | PixelBenderType::TBool4(..) => 4,
+            PixelBenderType::TFloat2x2(floats) => floats.len(),
+            PixelBenderType::TFloat3x3(floats) => floats.len(),
+            PixelBenderType::TFloat4x4(floats) => floats.len(),
+            PixelBenderType::TString(string) => usize::from(!string.is_empty()),
+        }
+    }
+
+    /// Whether this value has no components, which is only the case for an empty string.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Clamps every component of this value to the matching components of `min` and `max`.
+    ///
+    /// Strings, and bounds of a different type than this value, leave it unchanged.
+    pub fn clamp(&self, min: &PixelBenderType, max: &PixelBenderType) -> PixelBenderType {
+        fn f(value: f32, min: f32, max: f32) -> f32 {
+            value.max(min).min(max)
+        }
+        fn i(value: i16, min: i16, max: i16) -> i16 {
+            value.max(min).min(max)
+        }
+        fn m<const N: usize>(value: &[f32; N], min: &[f32; N], max: &[f32; N]) -> [f32; N] {
+            std::array::from_fn(|n| f(value[n], min[n], max[n]))
+        }
+
+        use PixelBenderType::*;
+        match (self, min, max) {
+            (TFloat(a), TFloat(a0), TFloat(a1)) => TFloat(f(*a, *a0, *a1)),
+            (TFloat2(a, b), TFloat2(a0, b0), TFloat2(a1, b1)) => {
+                TFloat2(f(*a, *a0, *a1), f(*b, *b0, *b1))
+            }
+            (TFloat3(a, b, c), TFloat3(a0, b0, c0), TFloat3(a1, b1, c1)) => {
+                TFloat3(f(*a, *a0, *a1), f(*b, *b0, *b1), f(*c, *c0, *c1))
+            }
+            (TFloat4(a, b, c, d), TFloat4(a0, b0, c0, d0), TFloat4(a1, b1, c1, d1)) => TFloat4(
+                f(*a, *a0, *a1),
+                f(*b, *b0, *b1),
+                f(*c, *c0, *c1),
+                f(*d, *d0, *d1),
+            ),
+            (TFloat2x2(v), TFloat2x2(v0), TFloat2x2(v1)) => TFloat2x2(m(v, v0, v1)),
+            (TFloat3x3(v), TFloat3x3(v0), TFloat3x3(v1)) => TFloat3x3(m(v, v0, v1)),
+            (TFloat4x4(v), TFloat4x4(v0), TFloat4x4(v1)) => TFloat4x4(m(v, v0, v1)),
+            (TInt(a), TInt(a0), TInt(a1)) => TInt(i(*a, *a0, *a1)),
+            (TInt2(a, b), TInt2(a0, b0), TInt2(a1, b1)) => TInt2(i(*a, *a0, *a1), i(*b, *b0, *b1)),
+            (TInt3(a, b, c), TInt3(a0, b0, c0), TInt3(a1, b1, c1)) => {
+                TInt3(i(*a, *a0, *a1), i(*b, *b0, *b1), i(*c, *c0, *c1))
+            }
+            (TInt4(a, b, c, d), TInt4(a0, b0, c0, d0), TInt4(a1, b1, c1, d1)) => TInt4(
+                i(*a, *a0, *a1),
+                i(*b, *b0, *b1),
+                i(*c, *c0, *c1),
+                i(*d, *d0, *d1),
+            ),
+            _ => self.clone(),
+        }
+    }
 }
 
 // FIXME - come up with a way to reduce duplication here
@@ -64,6 +145,10 @@ pub enum PixelBenderTypeOpcode {
     TInt3 = 0xA,
     TInt4 = 0xB,
     TString = 0xC,
+    TBool = 0xD,
+    TBool2 = 0xE,
+    TBool3 = 0xF,
+    TBool4 = 0x10,
 }
 
 #[derive(Debug, PartialEq, Copy, Clone)]
@@ -122,6 +207,60 @@ pub enum PixelBenderParamQualifier {
     Output = 2,
 }
 
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("Unknown Pixel Bender type name {0:?}")]
+pub struct UnknownTypeName(pub String);
+
+impl PixelBenderTypeOpcode {
+    /// The canonical Pixel Bender name of this type, e.g. `float3x3`.
+    ///
+    /// Unlike `Display`, which matches the type names reported to ActionScript,
+    /// this uses the names from the Pixel Bender language, which `from_name` accepts.
+    pub fn to_name(self) -> &'static str {
+        match self {
+            PixelBenderTypeOpcode::TFloat => "float",
+            PixelBenderTypeOpcode::TFloat2 => "float2",
+            PixelBenderTypeOpcode::TFloat3 => "float3",
+            PixelBenderTypeOpcode::TFloat4 => "float4",
+            PixelBenderTypeOpcode::TFloat2x2 => "float2x2",
+            PixelBenderTypeOpcode::TFloat3x3 => "float3x3",
+            PixelBenderTypeOpcode::TFloat4x4 => "float4x4",
+            PixelBenderTypeOpcode::TInt => "int",
+            PixelBenderTypeOpcode::TInt2 => "int2",
+            PixelBenderTypeOpcode::TInt3 => "int3",
+            PixelBenderTypeOpcode::TInt4 => "int4",
+            PixelBenderTypeOpcode::TString => "string",
+            PixelBenderTypeOpcode::TBool => "bool",
+            PixelBenderTypeOpcode::TBool2 => "bool2",
+            PixelBenderTypeOpcode::TBool3 => "bool3",
+            PixelBenderTypeOpcode::TBool4 => "bool4",
+        }
+    }
+
+    /// Parses a name produced by `to_name`.
+    pub fn from_name(name: &str) -> Result<Self, UnknownTypeName> {
+        Ok(match name {
+            "float" => PixelBenderTypeOpcode::TFloat,
+            "float2" => PixelBenderTypeOpcode::TFloat2,
+            "float3" => PixelBenderTypeOpcode::TFloat3,
+            "float4" => PixelBenderTypeOpcode::TFloat4,
+            "float2x2" => PixelBenderTypeOpcode::TFloat2x2,
+            "float3x3" => PixelBenderTypeOpcode::TFloat3x3,
+            "float4x4" => PixelBenderTypeOpcode::TFloat4x4,
+            "int" => PixelBenderTypeOpcode::TInt,
+            "int2" => PixelBenderTypeOpcode::TInt2,
+            "int3" => PixelBenderTypeOpcode::TInt3,
+            "int4" => PixelBenderTypeOpcode::TInt4,
+            "string" => PixelBenderTypeOpcode::TString,
+            "bool" => PixelBenderTypeOpcode::TBool,
+            "bool2" => PixelBenderTypeOpcode::TBool2,
+            "bool3" => PixelBenderTypeOpcode::TBool3,
+            "bool4" => PixelBenderTypeOpcode::TBool4,
+            _ => return Err(UnknownTypeName(name.to_string())),
+        })
+    }
+}
+
 impl Display for PixelBenderTypeOpcode {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -140,6 +279,10 @@ impl Display for PixelBenderTypeOpcode {
                 PixelBenderTypeOpcode::TInt3 => "int3",
                 PixelBenderTypeOpcode::TInt4 => "int4",
                 PixelBenderTypeOpcode::TString => "string",
+                PixelBenderTypeOpcode::TBool => "bool",
+                PixelBenderTypeOpcode::TBool2 => "bool2",
+                PixelBenderTypeOpcode::TBool3 => "bool3",
+                PixelBenderTypeOpcode::TBool4 => "bool4",
             }
         )
     }
@@ -726,9 +869,29 @@ fn read_value<R: Read>(
             data.read_i16::<LittleEndian>()?,
         )),
         PixelBenderTypeOpcode::TString => Ok(PixelBenderType::TString(read_string(data)?)),
+        PixelBenderTypeOpcode::TBool => Ok(PixelBenderType::TBool(read_bool(data)?)),
+        PixelBenderTypeOpcode::TBool2 => {
+            Ok(PixelBenderType::TBool2(read_bool(data)?, read_bool(data)?))
+        }
+        PixelBenderTypeOpcode::TBool3 => Ok(PixelBenderType::TBool3(
+            read_bool(data)?,
+            read_bool(data)?,
+            read_bool(data)?,
+        )),
+        PixelBenderTypeOpcode::TBool4 => Ok(PixelBenderType::TBool4(
+            read_bool(data)?,
+            read_bool(data)?,
+            read_bool(data)?,
+            read_bool(data)?,
+        )),
     }
 }
 
+/// Bools are stored like ints, with any non-zero value being true.
+fn read_bool<R: Read>(data: &mut R) -> Result<bool, Box<dyn std::error::Error>> {
+    Ok(data.read_i16::<LittleEndian>()? != 0)
+}
+
 fn read_uint24<R: Read>(data: &mut R) -> Result<u32, Box<dyn std::error::Error>> {
     let ch1 = data.read_u8()? as u32;
     let ch2 = data.read_u8()? as u32;
@@ -1,8 +1,9 @@
 use crate::pixel_bender::{
     Opcode, Operation, PixelBenderMetadata, PixelBenderParam, PixelBenderParamQualifier,
     PixelBenderReg, PixelBenderRegChannel, PixelBenderRegKind, PixelBenderShader, PixelBenderType,
-    PixelBenderTypeOpcode,
+    PixelBenderTypeOpcode, UnknownTypeName,
 };
+use num_traits::FromPrimitive;
 
 use super::parse_shader;
 
@@ -343,3 +344,94 @@ fn simple_shader() {
     let shader = parse_shader(shader).expect("Failed to parse shader");
     assert_eq!(shader, expected, "Shader parsed incorrectly!");
 }
+
+#[test]
+fn type_len() {
+    assert_eq!(PixelBenderType::TFloat(1.0).len(), 1);
+    assert_eq!(PixelBenderType::TFloat2(1.0, 2.0).len(), 2);
+    assert_eq!(PixelBenderType::TFloat4(1.0, 2.0, 3.0, 4.0).len(), 4);
+    assert_eq!(PixelBenderType::TFloat2x2([0.0; 4]).len(), 4);
+    assert_eq!(PixelBenderType::TFloat3x3([0.0; 9]).len(), 9);
+    assert_eq!(PixelBenderType::TFloat4x4([0.0; 16]).len(), 16);
+    assert_eq!(PixelBenderType::TInt(1).len(), 1);
+    assert_eq!(PixelBenderType::TInt3(1, 2, 3).len(), 3);
+    assert_eq!(PixelBenderType::TBool(true).len(), 1);
+    assert_eq!(PixelBenderType::TBool4(true, false, true, false).len(), 4);
+    assert_eq!(PixelBenderType::TString("name".to_string()).len(), 1);
+    assert_eq!(PixelBenderType::TString(String::new()).len(), 0);
+}
+
+#[test]
+fn type_is_empty() {
+    assert!(!PixelBenderType::TFloat(0.0).is_empty());
+    assert!(!PixelBenderType::TInt4(0, 0, 0, 0).is_empty());
+    assert!(!PixelBenderType::TFloat4x4([0.0; 16]).is_empty());
+    assert!(!PixelBenderType::TString("name".to_string()).is_empty());
+    assert!(PixelBenderType::TString(String::new()).is_empty());
+}
+
+#[test]
+fn type_opcode_name_round_trip() {
+    let opcodes: Vec<_> = (0..=0xFF)
+        .filter_map(PixelBenderTypeOpcode::from_u8)
+        .collect();
+    assert_eq!(opcodes.len(), 16);
+    for opcode in opcodes {
+        assert_eq!(
+            PixelBenderTypeOpcode::from_name(opcode.to_name()),
+            Ok(opcode)
+        );
+    }
+    assert_eq!(PixelBenderTypeOpcode::TFloat3x3.to_name(), "float3x3");
+    assert_eq!(PixelBenderTypeOpcode::TInt4.to_name(), "int4");
+}
+
+#[test]
+fn type_opcode_unknown_name() {
+    assert_eq!(
+        PixelBenderTypeOpcode::from_name("matrix3x3"),
+        Err(UnknownTypeName("matrix3x3".to_string()))
+    );
+    assert_eq!(
+        PixelBenderTypeOpcode::from_name("Float"),
+        Err(UnknownTypeName("Float".to_string()))
+    );
+    assert!(PixelBenderTypeOpcode::from_name("").is_err());
+}
+
+#[test]
+fn type_clamp() {
+    let min = PixelBenderType::TFloat2(0.0, -1.0);
+    let max = PixelBenderType::TFloat2(100.0, 1.0);
+    assert_eq!(
+        PixelBenderType::TFloat2(150.0, -5.0).clamp(&min, &max),
+        PixelBenderType::TFloat2(100.0, -1.0)
+    );
+    assert_eq!(
+        PixelBenderType::TFloat2(50.0, 0.5).clamp(&min, &max),
+        PixelBenderType::TFloat2(50.0, 0.5)
+    );
+
+    let min = PixelBenderType::TInt3(0, 0, 0);
+    let max = PixelBenderType::TInt3(10, 10, 10);
+    assert_eq!(
+        PixelBenderType::TInt3(-3, 5, 42).clamp(&min, &max),
+        PixelBenderType::TInt3(0, 5, 10)
+    );
+
+    let mut matrix = [0.5; 4];
+    matrix[3] = 2.0;
+    assert_eq!(
+        PixelBenderType::TFloat2x2(matrix).clamp(
+            &PixelBenderType::TFloat2x2([0.0; 4]),
+            &PixelBenderType::TFloat2x2([1.0; 4])
+        ),
+        PixelBenderType::TFloat2x2([0.5, 0.5, 0.5, 1.0])
+    );
+
+    // Mismatched bounds are ignored.
+    assert_eq!(
+        PixelBenderType::TFloat(5.0).clamp(&min, &max),
+        PixelBenderType::TFloat(5.0)
+    );
+}
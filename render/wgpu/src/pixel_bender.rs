@@ -573,6 +573,16 @@ pub(super) fn run_pixelbender_shader_impl(
                     PixelBenderType::TInt4(i1, i2, i3, i4) => {
                         FloatOrInt::Int(vec![*i1 as i32, *i2 as i32, *i3 as i32, *i4 as i32])
                     }
+                    PixelBenderType::TBool(b1) => FloatOrInt::Int(vec![*b1 as i32, 0, 0, 0]),
+                    PixelBenderType::TBool2(b1, b2) => {
+                        FloatOrInt::Int(vec![*b1 as i32, *b2 as i32, 0, 0])
+                    }
+                    PixelBenderType::TBool3(b1, b2, b3) => {
+                        FloatOrInt::Int(vec![*b1 as i32, *b2 as i32, *b3 as i32, 0])
+                    }
+                    PixelBenderType::TBool4(b1, b2, b3, b4) => {
+                        FloatOrInt::Int(vec![*b1 as i32, *b2 as i32, *b3 as i32, *b4 as i32])
+                    }
                     // We treat the input as being in column-major order. Despite what the Flash docs claim,
                     // this seems to be what Flash Player does.
                     PixelBenderType::TFloat2x2(arr) => FloatOrInt::Float(arr.to_vec()),
@@ -1,6 +1,7 @@
 use crate::test::Font;
 use chrono::{DateTime, Utc};
 use ruffle_core::{
+    backend::camera::{CameraAccess, CameraDevice},
     backend::ui::{
         DialogLoaderError, DialogResultFuture, FileDialogResult, FileFilter, FontDefinition,
         FullscreenError, LanguageIdentifier, MouseCursor, UiBackend, US_ENGLISH,
@@ -199,4 +200,9 @@ impl UiBackend for TestUiBackend {
     }
 
     fn close_file_dialog(&mut self) {}
+
+    fn request_camera_access(&mut self, _device: &CameraDevice) -> CameraAccess {
+        // Whether cameras may be used is decided by the `camera_permission` option.
+        CameraAccess::Allow
+    }
 }
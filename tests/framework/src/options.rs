@@ -6,6 +6,7 @@ use anyhow::{anyhow, Result};
 use approx::relative_eq;
 use image::ImageFormat;
 use regex::Regex;
+use ruffle_core::backend::camera::{CameraPermission, MockCameraBackend, MockCameraDevice};
 use ruffle_core::tag_utils::SwfMovie;
 use ruffle_core::{PlayerBuilder, PlayerMode, PlayerRuntime, ViewportDimensions};
 use ruffle_render::backend::RenderBackend;
@@ -152,6 +153,8 @@ pub struct PlayerOptions {
     with_video: bool,
     runtime: PlayerRuntime,
     mode: Option<PlayerMode>,
+    camera_permission: Option<CameraPermission>,
+    cameras: Vec<MockCameraDevice>,
 }
 
 impl PlayerOptions {
@@ -174,6 +177,15 @@ impl PlayerOptions {
             player_builder = player_builder.with_audio(TestAudioBackend::default());
         }
 
+        if !self.cameras.is_empty() {
+            player_builder =
+                player_builder.with_camera(MockCameraBackend::new(self.cameras.clone()));
+        }
+
+        if let Some(camera_permission) = self.camera_permission {
+            player_builder = player_builder.with_camera_permission(camera_permission);
+        }
+
         player_builder = player_builder
             .with_player_runtime(self.runtime)
             // Assume flashplayerdebugger is used in tests
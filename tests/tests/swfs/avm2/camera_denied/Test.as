@@ -0,0 +1,19 @@
+package {
+	import flash.display.MovieClip;
+	import flash.media.Camera;
+
+	public class Test extends MovieClip {
+		public function Test() {
+			// Devices are listed even when the user denied access to them.
+			trace("Camera.isSupported: " + Camera.isSupported);
+			trace("Camera.names: " + Camera.names);
+
+			var camera:Camera = Camera.getCamera();
+			trace("name: " + camera.name + ", index: " + camera.index);
+			trace("muted: " + camera.muted);
+			trace("fps: " + camera.fps + ", currentFPS: " + camera.currentFPS);
+			trace("activityLevel: " + camera.activityLevel);
+			trace("Same camera: " + (Camera.getCamera() === camera));
+		}
+	}
+}
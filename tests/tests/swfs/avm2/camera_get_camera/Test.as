@@ -0,0 +1,38 @@
+package {
+	import flash.display.MovieClip;
+	import flash.media.Camera;
+
+	public class Test extends MovieClip {
+		public function Test() {
+			var camera:Camera = Camera.getCamera();
+			trace("getCamera(): " + camera.name);
+
+			// Every way of asking for the first device returns the same camera.
+			trace("getCamera(null): " + (Camera.getCamera(null) === camera));
+			trace("getCamera(undefined): " + (Camera.getCamera(undefined) === camera));
+			trace('getCamera(""): ' + (Camera.getCamera("") === camera));
+			trace('getCamera("0"): ' + (Camera.getCamera("0") === camera));
+			trace('getCamera("Mock Camera"): ' + (Camera.getCamera("Mock Camera") === camera));
+
+			// There's only one device, and negative numbers aren't indices but names.
+			trace('getCamera("1"): ' + Camera.getCamera("1"));
+			trace('getCamera("-1"): ' + Camera.getCamera("-1"));
+
+			var modes:Array = camera.getModes();
+			for each (var mode:Object in modes) {
+				trace("mode: " + mode.width + "x" + mode.height + " at " + mode.fps + "fps");
+			}
+
+			var constructed:Camera = new Camera();
+			trace("new Camera() === getCamera(): " + (constructed === camera));
+			trace("new Camera(): " + constructed.name + ", index: " + constructed.index + ", muted: " + constructed.muted);
+
+			trace('"getCamera" in Camera: ' + ("getCamera" in Camera));
+			trace('"getCamera" in camera: ' + ("getCamera" in camera));
+			trace('"names" in Camera: ' + ("names" in Camera));
+			trace('"names" in camera: ' + ("names" in camera));
+			trace('"muted" in Camera: ' + ("muted" in Camera));
+			trace('"muted" in camera: ' + ("muted" in camera));
+		}
+	}
+}
@@ -0,0 +1,30 @@
+package {
+	import flash.display.MovieClip;
+	import flash.media.Camera;
+
+	public class Test extends MovieClip {
+		public function Test() {
+			trace("Camera.isSupported: " + Camera.isSupported);
+			trace("Camera.names: " + Camera.names);
+
+			var camera:Camera = Camera.getCamera();
+			describe(camera);
+			trace("Same camera: " + (Camera.getCamera() === camera));
+			trace("Same camera by name: " + (Camera.getCamera("Mock Camera") === camera));
+			trace("Missing camera: " + Camera.getCamera("2"));
+
+			var second:Camera = Camera.getCamera("1");
+			describe(second);
+			second.setMode(4, 2, 30);
+			trace("After setMode");
+			describe(second);
+		}
+
+		private function describe(camera:Camera):void {
+			trace("name: " + camera.name + ", index: " + camera.index);
+			trace("width: " + camera.width + ", height: " + camera.height);
+			trace("fps: " + camera.fps + ", currentFPS: " + camera.currentFPS);
+			trace("muted: " + camera.muted);
+		}
+	}
+}
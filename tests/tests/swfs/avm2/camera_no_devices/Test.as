@@ -0,0 +1,17 @@
+package {
+	import flash.display.MovieClip;
+	import flash.media.Camera;
+
+	public class Test extends MovieClip {
+		public function Test() {
+			trace("Camera.isSupported: " + Camera.isSupported);
+			trace("Camera.names.length: " + Camera.names.length);
+			trace("Camera.getCamera(): " + Camera.getCamera());
+
+			// Without any devices, a constructed camera can't be bound to one.
+			var camera:Camera = new Camera();
+			trace("name: \"" + camera.name + "\", index: " + camera.index);
+			trace("fps: " + camera.fps + ", currentFPS: " + camera.currentFPS);
+		}
+	}
+}
@@ -0,0 +1,37 @@
+package {
+	import flash.display.MovieClip;
+	import flash.media.Camera;
+
+	public class Test extends MovieClip {
+		public function Test() {
+			var camera:Camera = new Camera();
+
+			trace("quality: " + camera.quality + ", bandwidth: " + camera.bandwidth);
+			camera.setQuality(0, 80);
+			trace("setQuality(0, 80): quality: " + camera.quality + ", bandwidth: " + camera.bandwidth);
+			camera.setQuality(0, 150);
+			trace("setQuality(0, 150): quality: " + camera.quality + ", bandwidth: " + camera.bandwidth);
+
+			trace("keyFrameInterval: " + camera.keyFrameInterval);
+			var intervals:Array = [30, 0, 5000];
+			for each (var interval:int in intervals) {
+				camera.setKeyFrameInterval(interval);
+				trace("setKeyFrameInterval(" + interval + "): " + camera.keyFrameInterval);
+			}
+
+			trace("loopback: " + camera.loopback);
+			camera.setLoopback(true);
+			trace("setLoopback(true): " + camera.loopback);
+			camera.setLoopback();
+			trace("setLoopback(): " + camera.loopback);
+
+			trace("motionLevel: " + camera.motionLevel + ", motionTimeout: " + camera.motionTimeout);
+			camera.setMotionLevel(0, 500);
+			trace("setMotionLevel(0, 500): motionLevel: " + camera.motionLevel + ", motionTimeout: " + camera.motionTimeout);
+
+			trace("mirrored: " + camera.mirrored);
+			camera.mirrored = true;
+			trace("mirrored = true: " + camera.mirrored);
+		}
+	}
+}